@@ -1,21 +1,56 @@
 use serde::{Deserialize,Serialize};
 use std::collections::{HashMap,HashSet};
-use std::{cmp,fs,fmt};
-use log::{info,debug};
+use std::{cmp,fs,fmt,io};
+use log::{info,debug,warn};
+use thiserror::Error;
 
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 
-use ndarray::Array2;
-
 use crate::grid::CrosswordGrid;
+use crate::grid::EnumerationStyle;
+use crate::grid::PuzzleMeta;
+use crate::grid::RARE_LETTERS;
+use crate::grid::parse_answer_text;
+use crate::grid::Word;
 use crate::custom_hashmap_format;
 
 mod stats;
 mod recombination;
+mod similarity;
+mod output;
+mod word_bank;
+mod settings_builder;
+mod move_log;
+
+pub use similarity::DiversityMetric;
+pub use output::{OutputFormat,Manifest,ManifestEntry};
+pub use word_bank::CrosswordWordBank;
+pub use settings_builder::CrosswordGeneratorSettingsBuilder;
+pub use move_log::{AppliedMove,MoveLog};
+use similarity::{GridSimilarity,AdjacencyProduct,Jaccard,CanonicalDedup};
+
+/// What generate() should do if no complete grids were found
+#[derive(Clone,Copy,Debug,Eq,PartialEq,Deserialize,Serialize)]
+pub enum OnEmpty {
+    /// Return an error
+    Error,
+    /// Return the best of the partially filled ancestor grids instead
+    BestPartial,
+}
+
+impl OnEmpty {
+    /// Convert from the numeric setting used on the command line
+    pub fn from_setting(setting: usize) -> Self {
+        match setting {
+            1 => OnEmpty::BestPartial,
+            _ => OnEmpty::Error,
+        }
+    }
+}
 
-#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq,Deserialize,Serialize)]
 enum MoveType {
     Partition,
     PlaceWord,
@@ -40,11 +75,61 @@ fn generate_move_types_vec(place_word_weight: usize,
     move_types
 }
 
-fn calculate_similarity(adj1: &Array2<u8>, adj2: &Array2<u8>) -> f64 {
-    let union = (adj1 + adj2).iter().filter(|x| **x > 0).count() as f64;
-    let intersection = (adj1 * adj2).sum() as f64;
-    let similarity = intersection / union;
-    similarity
+/// Split a line of a dictionary file into the answer and its frequency. The frequency
+/// is an optional number at the end of the line, which defaults to one if not given.
+fn parse_dictionary_line(line: &str) -> (&str, usize) {
+    let trimmed = line.trim();
+    let split = trimmed.rfind(char::is_whitespace)
+        .and_then(|index| trimmed[index..].trim().parse::<usize>().ok().map(|frequency| (index, frequency)));
+    match split {
+        Some((index, frequency)) => (trimmed[..index].trim(), frequency),
+        None => (trimmed, 1),
+    }
+}
+
+/// Deterministically pick num_to_pick of the answers, choosing at random weighted by frequency.
+/// Frequencies of zero are treated as one. The chosen answers are returned in their original order.
+fn choose_by_frequency(answers: Vec<(&str, usize)>, num_to_pick: usize, seed: u64) -> Vec<&str> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut remaining: Vec<(usize, &str, usize)> = answers.into_iter()
+        .enumerate()
+        .map(|(index, (answer, frequency))| (index, answer, cmp::max(frequency, 1)))
+        .collect();
+    let mut chosen: Vec<(usize, &str)> = vec![];
+
+    while chosen.len() < num_to_pick && !remaining.is_empty() {
+        let positions: Vec<usize> = (0..remaining.len()).collect();
+        let position = *positions.choose_weighted(&mut rng, |p| remaining[*p].2 as f64)
+            .expect("All weights are positive");
+        let (index, answer, _frequency) = remaining.remove(position);
+        chosen.push((index, answer));
+    }
+
+    chosen.sort();
+    chosen.into_iter().map(|(_index, answer)| answer).collect()
+}
+
+/// Penalty applied to the score for each required answer missing from the grid, large enough
+/// to outweigh any other part of the score
+const REQUIRED_ANSWER_PENALTY: f64 = 100000.0;
+
+/// The required answers which aren't placed in the grid, in the order they were required
+fn missing_required_answers(grid: &CrosswordGrid, settings: &CrosswordGeneratorSettings) -> Vec<String> {
+    let all_answers = grid.all_answers();
+    let unplaced_answers = grid.unplaced_answers();
+    settings.required_answers.iter()
+        .filter(|answer| unplaced_answers.contains(answer) || !all_answers.contains(answer))
+        .cloned()
+        .collect()
+}
+
+/// How far the proportion of cells without a letter is from the target fraction of black
+/// cells, or 0 if there is no target.
+fn black_fraction_deviation(proportion_filled: f64, settings: &CrosswordGeneratorSettings) -> f64 {
+    match settings.target_black_fraction {
+        Some(target) => ((1.0 - proportion_filled) - target).abs(),
+        None => 0.0,
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
@@ -59,12 +144,60 @@ struct CrosswordGridScore {
     num_cycles: f64,
     num_intersections: f64,
     average_intersections: f64,
+    compactness: f64,
+    edge_words: f64,
+    intersection_centrality: f64,
+    spine_ratio: f64,
+    required_missing: f64,
     summary: f64,
 }
 
 impl CrosswordGridScore {
     fn new(grid: &CrosswordGrid, settings: &CrosswordGeneratorSettings) -> Self {
-        let (nrows, ncols) = grid.get_grid_dimensions();
+        if settings.normalize_scores {
+            CrosswordGridScore::new_normalized(grid, settings)
+        } else {
+            CrosswordGridScore::new_raw(grid, settings)
+        }
+    }
+
+    /// Score the grid, scaling each component to roughly [0,1] before applying the weights
+    /// so that the weights express the relative importance of each component. The individual
+    /// components are still reported unscaled.
+    fn new_normalized(grid: &CrosswordGrid, settings: &CrosswordGeneratorSettings) -> Self {
+        let mut score = CrosswordGridScore::new_raw(grid, settings);
+        if score.words_placed == 0.0 {
+            return score;
+        }
+
+        let max_square_cells = score.non_square_penalty + score.total_cells;
+        let total_words = score.words_placed + score.words_unplaced;
+        let normalized_cycles = if score.num_intersections > 0.0 { score.num_cycles / score.num_intersections } else { 0.0 };
+        let normalized_compactness = if score.compactness > 0.0 { 1.0 - 1.0 / score.compactness } else { 0.0 };
+
+        score.summary = - (score.non_square_penalty / max_square_cells) * (settings.weight_non_square as f64)
+                + score.proportion_filled * (settings.weight_prop_filled as f64)
+                + score.proportion_intersections * (settings.weight_prop_intersect as f64)
+                + normalized_cycles * (settings.weight_num_cycles as f64)
+                + (score.num_intersections / score.filled_cells) * (settings.weight_num_intersect as f64)
+                + score.average_intersections * (settings.weight_avg_intersect as f64)
+                + normalized_compactness * (settings.weight_compactness as f64)
+                + (score.words_placed / total_words) * (settings.weight_words_placed as f64)
+                - black_fraction_deviation(score.proportion_filled, settings) * (settings.weight_black_fraction as f64)
+                - (score.edge_words / score.words_placed) * (settings.weight_edge_words as f64)
+                + score.intersection_centrality * (settings.weight_intersection_centrality as f64)
+                - score.spine_ratio * (settings.weight_spine_penalty as f64)
+                - score.required_missing * REQUIRED_ANSWER_PENALTY;
+        score
+    }
+
+    fn new_raw(grid: &CrosswordGrid, settings: &CrosswordGeneratorSettings) -> Self {
+        if grid.count_placed_words() == 0 {
+            // Nothing to score, and several of the proportions below would be 0/0
+            return CrosswordGridScore::new_empty(grid.count_unplaced_words(), settings.required_answers.len());
+        }
+
+        let (nrows, ncols) = grid.scoring_dimensions();
         let total_cells = nrows * ncols;
         let non_square_penalty: usize = cmp::max(nrows, ncols).pow(2) - total_cells;
         let filled_cells: f64 = grid.count_filled_cells() as f64;
@@ -76,6 +209,11 @@ impl CrosswordGridScore {
         let double_counted_filled: f64 = filled_cells + num_intersections;
         let proportion_intersections: f64 = (num_intersections * 2.0) / double_counted_filled;
         let average_intersections: f64 = grid.average_intersections_per_word();
+        let compactness: f64 = grid.compactness();
+        let edge_words: f64 = grid.words_on_edge().len() as f64;
+        let intersection_centrality: f64 = grid.intersection_centrality();
+        let spine_ratio: f64 = grid.spine_ratio();
+        let required_missing: f64 = missing_required_answers(grid, settings).len() as f64;
 
         let summary: f64 = - (non_square_penalty as f64) * (settings.weight_non_square as f64)
                 + proportion_filled * (settings.weight_prop_filled as f64)
@@ -83,7 +221,13 @@ impl CrosswordGridScore {
                 + num_cycles * (settings.weight_num_cycles as f64)
                 + num_intersections * (settings.weight_num_intersect as f64)
                 + average_intersections * (settings.weight_avg_intersect as f64)
-                + words_placed * (settings.weight_words_placed as f64);
+                + compactness * (settings.weight_compactness as f64)
+                + words_placed * (settings.weight_words_placed as f64)
+                - black_fraction_deviation(proportion_filled, settings) * (settings.weight_black_fraction as f64)
+                - edge_words * (settings.weight_edge_words as f64)
+                + intersection_centrality * (settings.weight_intersection_centrality as f64)
+                - spine_ratio * (settings.weight_spine_penalty as f64)
+                - required_missing * REQUIRED_ANSWER_PENALTY;
         CrosswordGridScore {
             total_cells: total_cells as f64,
             non_square_penalty: non_square_penalty as f64,
@@ -95,28 +239,60 @@ impl CrosswordGridScore {
             num_cycles,
             num_intersections,
             average_intersections,
+            compactness,
+            edge_words,
+            intersection_centrality,
+            spine_ratio,
+            required_missing,
             summary,
         }
     }
 }
 
+impl CrosswordGridScore {
+    fn new_empty(words_unplaced: usize, required_missing: usize) -> Self {
+        CrosswordGridScore {
+            total_cells: 0.0,
+            non_square_penalty: 0.0,
+            proportion_filled: 0.0,
+            proportion_intersections: 0.0,
+            words_placed: 0.0,
+            words_unplaced: words_unplaced as f64,
+            filled_cells: 0.0,
+            num_cycles: 0.0,
+            num_intersections: 0.0,
+            average_intersections: 0.0,
+            compactness: 0.0,
+            edge_words: 0.0,
+            intersection_centrality: 0.0,
+            spine_ratio: 0.0,
+            required_missing: required_missing as f64,
+            summary: 0.0,
+        }
+    }
+}
+
 impl fmt::Display for CrosswordGridScore {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "GridScore[ summary:: {:.3} total_cells:: {:.0} filled_cells:: {:.0} \
                non_square_penalty:: {:.0} proportion_filled:: {:.3} proportion_intersections:: {:.3} average_intersections:: {:.3} \
-               words_placed:: {:.0} words_unplaced:: {:.0} num_cycles:: {:.0} num_intersections:: {:.0}]",
+               compactness:: {:.3} words_placed:: {:.0} words_unplaced:: {:.0} num_cycles:: {:.0} num_intersections:: {:.0} \
+               edge_words:: {:.0} intersection_centrality:: {:.3} spine_ratio:: {:.3} required_missing:: {:.0}]",
                self.summary, self.total_cells, self.filled_cells,
                self.non_square_penalty, self.proportion_filled, self.proportion_intersections, self.average_intersections,
-               self.words_placed, self.words_unplaced, self.num_cycles, self.num_intersections)
+               self.compactness, self.words_placed, self.words_unplaced, self.num_cycles, self.num_intersections,
+               self.edge_words, self.intersection_centrality, self.spine_ratio, self.required_missing)
     }
 }
 
-#[derive(Clone,Debug)]
+#[derive(Clone,Debug,Deserialize,Serialize)]
 struct CrosswordGridAttempt {
     grid: CrosswordGrid,
     score: CrosswordGridScore,
     move_counts: HashMap<MoveType, f64>,
     summary_score: isize,
+    #[serde(default)]
+    move_log: Option<MoveLog>,
 }
 
 impl CrosswordGridAttempt {
@@ -127,11 +303,13 @@ impl CrosswordGridAttempt {
         move_counts.insert(MoveType::PruneLeaves, 0.0);
         move_counts.insert(MoveType::Partition, 0.0);
         move_counts.insert(MoveType::Recombination, 0.0);
+        let move_log = if settings.record_move_log { Some(MoveLog::new(grid.clone())) } else { None };
         CrosswordGridAttempt {
             summary_score: score.summary as isize,
             score,
             grid,
             move_counts,
+            move_log,
         }
     }
 
@@ -143,6 +321,12 @@ impl CrosswordGridAttempt {
         *self.move_counts.get_mut(&move_type).unwrap() += 1.0;
     }
 
+    fn record_move(&mut self, applied_move: AppliedMove) {
+        if let Some(move_log) = self.move_log.as_mut() {
+            move_log.moves.push(applied_move);
+        }
+    }
+
     fn update_score(&mut self, settings: &CrosswordGeneratorSettings) {
         let score = CrosswordGridAttempt::score_grid(&self.grid, settings);
         self.score = score;
@@ -150,7 +334,7 @@ impl CrosswordGridAttempt {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone,Debug,PartialEq,Deserialize,Serialize)]
 pub struct CrosswordGeneratorSettings {
     seed: u64,
     moves_between_scores: usize,
@@ -167,6 +351,25 @@ pub struct CrosswordGeneratorSettings {
     weight_num_intersect: usize,
     weight_avg_intersect: usize,
     weight_words_placed: usize,
+    weight_compactness: usize,
+    weight_black_fraction: usize,
+    weight_edge_words: usize,
+    weight_intersection_centrality: usize,
+    weight_spine_penalty: usize,
+    target_black_fraction: Option<f64>,
+    enumeration_style: EnumerationStyle,
+    diversity_metric: DiversityMetric,
+    hash_dedup: bool,
+    diversity_weight: f64,
+    normalize_scores: bool,
+    require_min_cycles: usize,
+    forbid_solid_squares: bool,
+    prefer_long_words: bool,
+    record_move_log: bool,
+    keep_best_ever: bool,
+    on_empty: OnEmpty,
+    // Answers which must be placed, set with CrosswordGenerator::require_all
+    required_answers: Vec<String>,
 }
 
 impl CrosswordGeneratorSettings {
@@ -174,6 +377,17 @@ impl CrosswordGeneratorSettings {
         CrosswordGeneratorSettings::new_from_hashmap(HashMap::new())
     }
 
+    /// Derive a seed from some text, e.g. the name of the puzzle, using the 64-bit FNV-1a
+    /// hash so the seed is the same across runs and platforms.
+    pub fn seed_from_str(s: &str) -> u64 {
+        crate::utils::fnv1a_64(s)
+    }
+
+    /// Summary score of the grid under these settings
+    pub(crate) fn score_summary(&self, grid: &CrosswordGrid) -> f64 {
+        CrosswordGridScore::new(grid, self).summary
+    }
+
     pub fn new_from_hashmap(settings: HashMap<&str, usize>) -> Self {
         CrosswordGeneratorSettings {
             seed: *settings.get("seed").unwrap_or(&13) as u64,
@@ -190,12 +404,65 @@ impl CrosswordGeneratorSettings {
             weight_num_intersect: *settings.get("weight-num-intersect").unwrap_or(&100),
             weight_avg_intersect: *settings.get("weight-avg-intersect").unwrap_or(&5000),
             weight_words_placed: *settings.get("weight-words-placed").unwrap_or(&10),
+            weight_compactness: *settings.get("weight-compactness").unwrap_or(&0),
+            weight_edge_words: *settings.get("weight-edge-words").unwrap_or(&0),
+            weight_intersection_centrality: *settings.get("weight-intersection-centrality").unwrap_or(&0),
+            weight_spine_penalty: *settings.get("weight-spine-penalty").unwrap_or(&0),
+            weight_black_fraction: *settings.get("weight-black-fraction").unwrap_or(&1000),
+            // Given as a percentage, and only scored if set
+            target_black_fraction: settings.get("target-black-fraction").map(|percent| *percent as f64 / 100.0),
+            // Given as a percentage, so the default of 100 means the full similarity is applied
+            diversity_weight: *settings.get("diversity-weight").unwrap_or(&100) as f64 / 100.0,
+            forbid_solid_squares: *settings.get("forbid-solid-squares").unwrap_or(&0) > 0,
+            prefer_long_words: *settings.get("prefer-long-words").unwrap_or(&0) > 0,
+            record_move_log: *settings.get("record-move-log").unwrap_or(&0) > 0,
+            keep_best_ever: *settings.get("keep-best-ever").unwrap_or(&0) > 0,
+            require_min_cycles: *settings.get("require-min-cycles").unwrap_or(&0),
+            normalize_scores: *settings.get("normalize-scores").unwrap_or(&0) > 0,
+            hash_dedup: *settings.get("hash-dedup").unwrap_or(&0) > 0,
+            diversity_metric: DiversityMetric::from_setting(*settings.get("diversity-metric").unwrap_or(&0)),
+            on_empty: OnEmpty::from_setting(*settings.get("on-empty").unwrap_or(&0)),
+            enumeration_style: if *settings.get("us-enumeration").unwrap_or(&0) > 0 { EnumerationStyle::Us } else { EnumerationStyle::Uk },
             move_types: generate_move_types_vec(6, 2, 0),
+            required_answers: vec![],
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Error,Debug,PartialEq)]
+pub enum GeneratorError {
+    #[error("Generators have different word lists, so their populations can't be merged")]
+    IncompatibleWordLists,
+
+    #[error("No complete grids were generated")]
+    NoCompleteGrids,
+
+    #[error("Unable to save or resume from checkpoint: {0}")]
+    Checkpoint(String),
+}
+
+/// A rough estimate of how much work generating a grid from the word list will be
+#[derive(Clone,Debug,PartialEq)]
+pub struct Workload {
+    pub num_words: usize,
+    pub total_letters: usize,
+    /// Words sharing no letters with any other word, which can never be connected to the grid
+    pub num_singletons: usize,
+    /// Heuristic from 0 (easy) to 1 (hard), averaging the proportion of other words each
+    /// word shares no letters with and the proportion of letters which are rare
+    pub difficulty: f64,
+}
+
+/// User supplied predicate deciding whether a grid may enter the next generation
+struct GridFilter(Box<dyn Fn(&CrosswordGrid) -> bool>);
+
+impl fmt::Debug for GridFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GridFilter")
+    }
+}
+
+#[derive(Debug,Deserialize,Serialize)]
 pub struct CrosswordGenerator {
     current_generation_complete: Vec<CrosswordGridAttempt>,
     next_generation_complete: Vec<CrosswordGridAttempt>,
@@ -203,42 +470,284 @@ pub struct CrosswordGenerator {
     next_generation_ancestors: Vec<CrosswordGridAttempt>,
     round: usize,
     pub settings: CrosswordGeneratorSettings,
+    // State used to detect convergence, kept here so that it survives a checkpoint
+    best_overall_score: isize,
+    rounds_no_increase: usize,
+    last_generation_stringified: String,
+    reached_convergence: bool,
+    // Highest scoring complete grid from any round, only kept with the keep-best-ever setting
+    #[serde(default)]
+    best_ever: Option<CrosswordGridAttempt>,
+    // Closures can't be serialized, so the filter must be set again after resuming
+    #[serde(skip)]
+    grid_filter: Option<GridFilter>,
 }
 
 impl CrosswordGenerator {
-    pub fn new_from_file_default(filename: &str) -> Self {
+    pub fn new_from_file_default(filename: &str) -> Result<Self, io::Error> {
         CrosswordGenerator::new_from_file(filename, HashMap::new())
     }
 
+    /// Create a generator from the contents of a clue file. The file can start with
+    /// `# key: value` lines giving details such as the title, which are attached to every grid.
     pub fn new_from_file_contents(contents: &str, settings_map: HashMap<&str, usize>) -> Self {
-        let words: Vec<&str> = contents.split('\n').collect();
-        CrosswordGenerator::new_from_singletons(words, settings_map)
+        let (meta, words) = PuzzleMeta::parse_header(contents);
+        let mut generator = CrosswordGenerator::new_from_singletons(words, settings_map);
+        for attempt in generator.current_generation_ancestors.iter_mut() {
+            attempt.grid.set_meta(meta.clone());
+        }
+        generator
+    }
+
+    /// Create a generator from a clue file, returning an error if the file can't be read
+    pub fn new_from_file(filename: &str, settings_map: HashMap<&str, usize>) -> Result<Self, io::Error> {
+        let contents = fs::read_to_string(filename)?;
+        Ok(CrosswordGenerator::new_from_file_contents(&contents, settings_map))
     }
 
-    pub fn new_from_file(filename: &str, settings_map: HashMap<&str, usize>) -> Self {
-        let contents = fs::read_to_string(filename).unwrap();
-        CrosswordGenerator::new_from_file_contents(&contents, settings_map)
+    /// Create a generator from a dictionary file, using a subset of target_word_count
+    /// of its answers. Each line of the file is an answer (in the same format as a clue file),
+    /// optionally followed by a frequency e.g. "APPLE 1200". Answers are chosen at random,
+    /// but deterministically given the seed, with more frequent answers more likely to be chosen.
+    pub fn new_from_dictionary(filename: &str, target_word_count: usize, settings_map: HashMap<&str, usize>) -> Result<Self, io::Error> {
+        let contents = fs::read_to_string(filename)?;
+        let answers: Vec<(&str, usize)> = contents.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(parse_dictionary_line)
+            .collect();
+        let seed = CrosswordGeneratorSettings::new_from_hashmap(settings_map.clone()).seed;
+        let words = choose_by_frequency(answers, target_word_count, seed);
+        Ok(CrosswordGenerator::new_from_singletons(words, settings_map))
     }
 
     pub fn new_from_singletons(words: Vec<&str>, settings_map: HashMap<&str, usize>) -> Self {
         let settings = CrosswordGeneratorSettings::new_from_hashmap(settings_map);
+        CrosswordGenerator::new_from_singletons_with_settings(words, settings)
+    }
+
+    /// As new_from_singletons, but with settings made e.g. by CrosswordGeneratorSettingsBuilder
+    pub fn new_from_singletons_with_settings(words: Vec<&str>, settings: CrosswordGeneratorSettings) -> Self {
+        let grids = CrosswordGrid::random_singleton_grids(words, settings.seed, settings.enumeration_style);
+        CrosswordGenerator::new_from_singleton_grids(grids, settings)
+    }
 
+    fn new_from_singleton_grids(grids: Vec<CrosswordGrid>, settings: CrosswordGeneratorSettings) -> Self {
         let mut singletons: Vec<CrosswordGridAttempt> = vec![];
 
-        for grid in CrosswordGrid::random_singleton_grids(words, settings.seed) {
+        for grid in grids {
             singletons.push(CrosswordGridAttempt::new(grid, &settings));
         }
 
         info!("First of first generation is {}", singletons[0].grid.to_string());
 
-        CrosswordGenerator {
+        let mut generator = CrosswordGenerator {
             current_generation_ancestors: singletons,
             current_generation_complete: vec![],
             next_generation_ancestors: vec![],
             next_generation_complete: vec![],
             round: 0,
             settings,
+            best_overall_score: -1,
+            rounds_no_increase: 0,
+            last_generation_stringified: String::new(),
+            reached_convergence: false,
+            best_ever: None,
+            grid_filter: None,
+        };
+        generator.last_generation_stringified = generator.stringified_output();
+        generator
+    }
+
+    /// Only allow grids for which the filter returns true into the next generation.
+    /// Children failing the filter are discarded before they are scored.
+    pub fn set_grid_filter<F: Fn(&CrosswordGrid) -> bool + 'static>(&mut self, filter: F) {
+        self.grid_filter = Some(GridFilter(Box::new(filter)));
+    }
+
+    fn passes_grid_filter(&self, grid: &CrosswordGrid) -> bool {
+        match &self.grid_filter {
+            Some(GridFilter(filter)) => filter(grid),
+            None => true,
+        }
+    }
+
+    /// Save the full state of the generator to a JSON file, so that generation can be
+    /// continued later with resume_from_checkpoint.
+    pub fn save_checkpoint(&self, path: &str) -> Result<(), GeneratorError> {
+        let serialized = serde_json::to_string(self).map_err(|error| GeneratorError::Checkpoint(error.to_string()))?;
+        fs::write(path, serialized).map_err(|error| GeneratorError::Checkpoint(error.to_string()))
+    }
+
+    /// Load a generator from a file written by save_checkpoint. Generating from the resumed
+    /// generator gives exactly the same results as if generation had never been interrupted.
+    pub fn resume_from_checkpoint(path: &str) -> Result<Self, GeneratorError> {
+        let contents = fs::read_to_string(path).map_err(|error| GeneratorError::Checkpoint(error.to_string()))?;
+        serde_json::from_str(&contents).map_err(|error| GeneratorError::Checkpoint(error.to_string()))
+    }
+
+    /// Estimate the workload from the word list, without running any generation.
+    pub fn estimate_workload(&self) -> Workload {
+        let answers = self.answers();
+        let letter_sets: Vec<HashSet<char>> = answers.iter().map(|answer| answer.chars().collect()).collect();
+
+        let num_words = answers.len();
+        let total_letters: usize = answers.iter().map(|answer| answer.chars().count()).sum();
+        let rare_letters = answers.iter().flat_map(|answer| answer.chars()).filter(|c| RARE_LETTERS.contains(*c)).count();
+
+        let mut num_singletons = 0;
+        let mut total_isolation = 0.0;
+        for (i, letters) in letter_sets.iter().enumerate() {
+            let num_disjoint = letter_sets.iter().enumerate()
+                .filter(|(j, other_letters)| *j != i && letters.is_disjoint(other_letters))
+                .count();
+            if num_disjoint + 1 == num_words && num_words > 1 {
+                num_singletons += 1;
+            }
+            if num_words > 1 {
+                total_isolation += num_disjoint as f64 / (num_words - 1) as f64;
+            }
+        }
+
+        let difficulty = if num_words == 0 || total_letters == 0 {
+            0.0
+        } else {
+            (total_isolation / num_words as f64 + rare_letters as f64 / total_letters as f64) / 2.0
+        };
+
+        Workload {
+            num_words,
+            total_letters,
+            num_singletons,
+            difficulty,
+        }
+    }
+
+    /// Answers of the words used by this generator, ordered by word id
+    fn answers(&self) -> Vec<String> {
+        match self.current_generation_ancestors.iter().chain(self.current_generation_complete.iter()).next() {
+            Some(attempt) => attempt.grid.all_answers(),
+            None => vec![],
+        }
+    }
+
+    /// Require that every grid output contains all of these answers, e.g. the theme answers
+    /// of a themed puzzle. Grids missing any of them are heavily penalised, and are only
+    /// output if no complete grid contains them all, in which case missing_required_in_best
+    /// gives the answers which couldn't be placed. Answers are given in the clue file format.
+    pub fn require_all(&mut self, word_texts: &[&str]) {
+        let all_answers = self.answers();
+        let mut required_answers: Vec<String> = vec![];
+        for answer in word_texts.iter().filter_map(|word_text| parse_answer_text(word_text)) {
+            if !all_answers.contains(&answer) {
+                warn!("Required answer {} is not in the word list, so can never be placed", answer);
+            }
+            required_answers.push(answer);
         }
+        self.settings.required_answers = required_answers;
+
+        let settings = self.settings.clone();
+        for grid_attempt in self.current_generation_ancestors.iter_mut().chain(self.current_generation_complete.iter_mut()) {
+            grid_attempt.update_score(&settings);
+        }
+        let ancestors: Vec<CrosswordGridAttempt> = self.current_generation_ancestors.drain(..).collect();
+        self.current_generation_ancestors = self.pick_best_varied(ancestors, self.settings.num_per_generation);
+        let complete: Vec<CrosswordGridAttempt> = self.current_generation_complete.drain(..).collect();
+        self.current_generation_complete = self.pick_best_varied(complete, self.settings.num_per_generation);
+        if let Some(best_ever) = self.best_ever.as_mut() {
+            best_ever.update_score(&settings);
+        }
+    }
+
+    /// Add more words, given in the clue file format, to every grid in the population, e.g.
+    /// after writing extra clues, so that a further call to generate extends the current grids
+    /// rather than starting over. Answers already in the word list are skipped. Complete grids
+    /// become ancestors again, since they now have unplaced words, and the round counter and
+    /// convergence checks are reset.
+    pub fn add_words(&mut self, words: Vec<&str>) {
+        let mut all_answers = self.answers();
+        let mut new_words: Vec<Word> = vec![];
+        let mut added_word_strs: Vec<String> = vec![];
+        for word_str in words {
+            match Word::new_parsed_with_style(word_str, self.settings.enumeration_style) {
+                Ok(word) if all_answers.contains(&word.word_text) => {
+                    warn!("Answer {} is already in the word list, so is not added again", word.word_text);
+                },
+                Ok(word) => {
+                    all_answers.push(word.word_text.clone());
+                    new_words.push(word);
+                    added_word_strs.push(word_str.to_string());
+                },
+                Err(_) => warn!("Skipping word which couldn't be parsed: {}", word_str),
+            }
+        }
+        if new_words.is_empty() {
+            return;
+        }
+
+        let mut ancestors: Vec<CrosswordGridAttempt> = self.current_generation_ancestors.drain(..).collect();
+        ancestors.append(&mut self.current_generation_complete);
+        let settings = self.settings.clone();
+        for grid_attempt in ancestors.iter_mut() {
+            for word in new_words.iter() {
+                grid_attempt.grid.add_parsed_unplaced_word(word.clone());
+            }
+            grid_attempt.record_move(AppliedMove::AddWords { words: added_word_strs.clone() });
+            grid_attempt.update_score(&settings);
+        }
+        self.current_generation_ancestors = self.pick_best_varied(ancestors, self.settings.num_per_generation);
+
+        // The best grid so far doesn't have the new words, so can't be output any more
+        self.best_ever = None;
+        self.round = 0;
+        self.best_overall_score = -1;
+        self.rounds_no_increase = 0;
+        self.reached_convergence = false;
+        self.last_generation_stringified = self.stringified_output();
+    }
+
+    /// Required answers which are not placed in the best grid that would be output. Returns
+    /// an empty list if no complete grids have been generated yet.
+    pub fn missing_required_in_best(&self) -> Vec<String> {
+        match self.best_attempts(1).first() {
+            Some(best) => missing_required_answers(&best.grid, &self.settings),
+            None => vec![],
+        }
+    }
+
+    /// Hex digest of the sorted answers and all the settings, which can be used as a cache key
+    /// for the output. This uses the same FNV-1a hash as seed_from_str, so the value doesn't
+    /// change between runs or platforms.
+    pub fn input_fingerprint(&self) -> String {
+        let mut answers = self.answers();
+        answers.sort();
+        let settings_json = serde_json::to_string(&self.settings).expect("Settings should always be serializable");
+        let input = format!("{}\n{}", answers.join("\n"), settings_json);
+        format!("{:016x}", CrosswordGeneratorSettings::seed_from_str(&input))
+    }
+
+    /// Pool the population of another generator, e.g. one run with a different seed, into this
+    /// one. The other grids are rescored using these settings, then the best varied grids are
+    /// kept as usual. The generators must have been created from the same word list.
+    pub fn merge_population(&mut self, other: CrosswordGenerator) -> Result<(), GeneratorError> {
+        if self.answers() != other.answers() {
+            return Err(GeneratorError::IncompatibleWordLists);
+        }
+
+        let rescore = |mut attempts: Vec<CrosswordGridAttempt>, settings: &CrosswordGeneratorSettings| {
+            for attempt in attempts.iter_mut() {
+                attempt.update_score(settings);
+            }
+            attempts
+        };
+
+        let mut ancestors: Vec<CrosswordGridAttempt> = self.current_generation_ancestors.drain(..).collect();
+        ancestors.extend(rescore(other.current_generation_ancestors, &self.settings));
+        self.current_generation_ancestors = self.pick_best_varied(ancestors, self.settings.num_per_generation);
+
+        let mut complete: Vec<CrosswordGridAttempt> = self.current_generation_complete.drain(..).collect();
+        complete.extend(rescore(other.current_generation_complete, &self.settings));
+        self.current_generation_complete = self.pick_best_varied(complete, self.settings.num_per_generation);
+        Ok(())
     }
 
     fn attempt_partition(&self, grid_attempt: &mut CrosswordGridAttempt, seed: u64) -> Option<CrosswordGridAttempt> {
@@ -249,6 +758,8 @@ impl CrosswordGenerator {
 
             grid_attempt.increment_move_count(MoveType::Partition);
             other_half.increment_move_count(MoveType::Partition);
+            grid_attempt.record_move(AppliedMove::Partition { seed, kept_other_half: false });
+            other_half.record_move(AppliedMove::Partition { seed, kept_other_half: true });
             other_half.update_score(&self.settings);
             grid_attempt.update_score(&self.settings);
 
@@ -271,7 +782,13 @@ impl CrosswordGenerator {
         *self.settings.move_types.choose(&mut rng).unwrap()
     }
 
-    fn produce_child(&self, grid_attempt: &CrosswordGridAttempt, seed: u64) -> CrosswordGridAttempt {
+    /// Place a random word using the strategy chosen in the settings. Preferring long words
+    /// and forbidding solid squares can be used together.
+    fn place_random_word(&self, grid: &mut CrosswordGrid, seed: u64) -> bool {
+        grid.place_random_word_with_options(seed, self.settings.prefer_long_words, self.settings.forbid_solid_squares)
+    }
+
+    fn produce_child(&self, grid_attempt: &CrosswordGridAttempt, seed: u64) -> Option<CrosswordGridAttempt> {
         let mut copied = grid_attempt.clone();
         let mut moves = 0;
         let mut success = true;
@@ -281,14 +798,16 @@ impl CrosswordGenerator {
             debug!("Picked move {:?}", random_move);
             match random_move {
                 MoveType::PlaceWord => {
-                    success = copied.grid.place_random_word(extended_seed);
+                    success = self.place_random_word(&mut copied.grid, extended_seed);
                     if success {
                         copied.increment_move_count(MoveType::PlaceWord);
+                        copied.record_move(AppliedMove::PlaceWord { seed: extended_seed });
                     }
                 },
                 MoveType::PruneLeaves => {
                     copied.grid.remove_random_leaves(1, extended_seed);
                     copied.increment_move_count(MoveType::PruneLeaves);
+                    copied.record_move(AppliedMove::PruneLeaves { seed: extended_seed });
                 },
                 MoveType::Partition => {
                     self.partition_keep_best(&mut copied, extended_seed);
@@ -297,24 +816,33 @@ impl CrosswordGenerator {
             }
             moves += 1;
         }
-        copied.update_score(&self.settings);
-        copied
+        if self.passes_grid_filter(&copied.grid) {
+            copied.update_score(&self.settings);
+            Some(copied)
+        } else {
+            None
+        }
     }
 
-    fn fill_grid(&self, grid_attempt: &CrosswordGridAttempt, seed: u64) -> CrosswordGridAttempt {
+    fn fill_grid(&self, grid_attempt: &CrosswordGridAttempt, seed: u64) -> Option<CrosswordGridAttempt> {
         let mut copied = grid_attempt.clone();
         let mut moves = 0;
         let mut success = true;
         while success {
             let extended_seed: u64 = seed.wrapping_add(moves as u64);
-            success = copied.grid.place_random_word(extended_seed);
+            success = self.place_random_word(&mut copied.grid, extended_seed);
             if success {
                 copied.increment_move_count(MoveType::PlaceWord);
+                copied.record_move(AppliedMove::PlaceWord { seed: extended_seed });
             }
             moves += 1;
         }
-        copied.update_score(&self.settings);
-        copied
+        if self.passes_grid_filter(&copied.grid) {
+            copied.update_score(&self.settings);
+            Some(copied)
+        } else {
+            None
+        }
     }
 
     fn next_generation(&mut self) {
@@ -327,8 +855,9 @@ impl CrosswordGenerator {
             debug!("Considering extensions of grid:\n{}", grid_attempt.grid.to_string());
             let seed = (grid_attempt.summary_score as u64).wrapping_add(self.round as u64);
             for child_index in 0..self.settings.num_children {
-                let child = self.produce_child(&grid_attempt, seed.wrapping_add(child_index as u64));
-                self.next_generation_ancestors.push(child);
+                if let Some(child) = self.produce_child(&grid_attempt, seed.wrapping_add(child_index as u64)) {
+                    self.next_generation_ancestors.push(child);
+                }
             }
         }
         info!("GENERATED ANCESTORS. Current_ancestors: {}, current_complete: {}, next_ancestors: {}, next_complete: {}",
@@ -349,8 +878,9 @@ impl CrosswordGenerator {
         for grid_attempt in self.current_generation_ancestors.iter() {
             let seed = grid_attempt.summary_score as u64;
             for child_index in 0..self.settings.num_children {
-                let child = self.fill_grid(&grid_attempt, seed.wrapping_add(child_index as u64));
-                self.next_generation_complete.push(child);
+                if let Some(child) = self.fill_grid(&grid_attempt, seed.wrapping_add(child_index as u64)) {
+                    self.next_generation_complete.push(child);
+                }
             }
         }
 
@@ -374,6 +904,14 @@ impl CrosswordGenerator {
     }
 
     fn restrict_to_unique(&self, grid_attempts: Vec<CrosswordGridAttempt>) -> Vec<CrosswordGridAttempt> {
+        if self.settings.hash_dedup {
+            self.restrict_to_unique_by_hash(grid_attempts)
+        } else {
+            self.restrict_to_unique_by_string(grid_attempts)
+        }
+    }
+
+    fn restrict_to_unique_by_string(&self, grid_attempts: Vec<CrosswordGridAttempt>) -> Vec<CrosswordGridAttempt> {
         let mut unique_children_hashes: HashSet<String> = HashSet::new();
         let mut unique_children: Vec<CrosswordGridAttempt> = vec![];
 
@@ -386,12 +924,37 @@ impl CrosswordGenerator {
         unique_children
     }
 
+    // Only stores a 64-bit hash per grid, rather than the full string, at the
+    // cost of a tiny risk of discarding a unique grid due to a hash collision
+    fn restrict_to_unique_by_hash(&self, grid_attempts: Vec<CrosswordGridAttempt>) -> Vec<CrosswordGridAttempt> {
+        let mut unique_children_hashes: HashSet<u64> = HashSet::new();
+        let mut unique_children: Vec<CrosswordGridAttempt> = vec![];
+
+        for child in grid_attempts {
+            let is_new_child = unique_children_hashes.insert(child.grid.structural_hash());
+            if is_new_child {
+                unique_children.push(child);
+            }
+        }
+        unique_children
+    }
+
     fn pick_best_varied(&self, grid_attempts: Vec<CrosswordGridAttempt>, num_to_pick: usize) -> Vec<CrosswordGridAttempt> {
+        match self.settings.diversity_metric {
+            DiversityMetric::AdjacencyProduct => self.pick_best_varied_by::<AdjacencyProduct>(grid_attempts, num_to_pick),
+            DiversityMetric::Jaccard => self.pick_best_varied_by::<Jaccard>(grid_attempts, num_to_pick),
+            DiversityMetric::CanonicalDedup => self.pick_best_varied_by::<CanonicalDedup>(grid_attempts, num_to_pick),
+        }
+    }
+
+    /// Greedily pick the best grids, after each pick penalising the remaining grids
+    /// according to how similar they are to the grid just picked.
+    fn pick_best_varied_by<M: GridSimilarity>(&self, grid_attempts: Vec<CrosswordGridAttempt>, num_to_pick: usize) -> Vec<CrosswordGridAttempt> {
         let mut best_attempts: Vec<CrosswordGridAttempt> = vec![];
 
         let mut unique_children = self.restrict_to_unique(grid_attempts);
         let mut unique_children_summaries: Vec<isize> = unique_children.iter().map(|x| x.summary_score).collect();
-        let mut unique_children_adjacencies: Vec<Array2<u8>> = unique_children.iter().map(|x| x.grid.to_graph_adjacency_matrix()).collect();
+        let mut unique_children_features: Vec<M::Features> = unique_children.iter().map(|x| M::features(&x.grid)).collect();
         let mut unique_children_adjusted_scores: Vec<isize> = unique_children_summaries.iter().cloned().collect();
 
         while best_attempts.len() < num_to_pick && unique_children_summaries.len() > 0 {
@@ -401,18 +964,16 @@ impl CrosswordGenerator {
 
             let best_attempt = unique_children.remove(best_index);
             unique_children_summaries.remove(best_index);
-            unique_children_adjacencies.remove(best_index);
+            let best_features = unique_children_features.remove(best_index);
             unique_children_adjusted_scores.remove(best_index);
 
             debug!("Grid has score {}\n{}", best_attempt.score, best_attempt.grid.to_string());
-            let best_adjacency = &best_attempt.grid.to_graph_adjacency_matrix();
             let existing_grid_strings: Vec<String> = best_attempts.iter().map(|x| x.grid.to_string()).collect();
             debug!("Existing grids:\n{}", existing_grid_strings.join("\n\n"));
 
             for i in 0..unique_children.len() {
-                let adjacency = &unique_children_adjacencies[i];
-                let similarity = calculate_similarity(adjacency, best_adjacency);
-                let adjusted = ((unique_children_summaries[i] as f64) * (1.0 - similarity)) as isize;
+                let similarity = M::similarity(&unique_children_features[i], &best_features);
+                let adjusted = ((unique_children_summaries[i] as f64) * (1.0 - self.settings.diversity_weight * similarity)) as isize;
                 if adjusted < unique_children_adjusted_scores[i] {
                     unique_children_adjusted_scores[i] = adjusted;
                 }
@@ -429,14 +990,53 @@ impl CrosswordGenerator {
         best_attempts
     }
 
+    /// The best attempts of the current generation. Only grids with at least the required number
+    /// of cycles and all the required answers are output, unless none qualify, in which case
+    /// the best grids are output anyway. Attempts are ordered by score, highest first, with ties
+    /// broken by comparing the canonical strings of the grids so the order is deterministic.
+    fn best_attempts(&self, num_to_output: usize) -> Vec<&CrosswordGridAttempt> {
+        let mut candidates: Vec<&CrosswordGridAttempt> = self.current_generation_complete.iter().collect();
+        if let Some(best_ever) = self.best_ever.as_ref() {
+            let best_ever_string = best_ever.grid.to_string();
+            if !candidates.iter().any(|grid_attempt| grid_attempt.grid.to_string() == best_ever_string) {
+                candidates.push(best_ever);
+            }
+        }
+        let mut qualifying: Vec<&CrosswordGridAttempt> = candidates.iter()
+            .filter(|grid_attempt| grid_attempt.score.num_cycles as usize >= self.settings.require_min_cycles)
+            .filter(|grid_attempt| grid_attempt.score.required_missing == 0.0)
+            .cloned()
+            .collect();
+        if qualifying.is_empty() {
+            info!("No grids have at least {} cycles and all required answers, so outputting the best grids anyway",
+                  self.settings.require_min_cycles);
+            qualifying = candidates;
+        }
+        qualifying.sort_by(|a, b| {
+            b.score.summary.partial_cmp(&a.score.summary).unwrap_or(cmp::Ordering::Equal)
+                .then_with(|| a.grid.canonical_string().cmp(&b.grid.canonical_string()))
+        });
+        qualifying.truncate(num_to_output);
+        qualifying
+    }
+
     fn output_best(&self, num_to_output: usize) -> Vec<CrosswordGrid> {
         let mut output: Vec<CrosswordGrid> = vec![];
-        for grid_attempt in self.current_generation_complete.iter().take(num_to_output) {
+        for grid_attempt in self.best_attempts(num_to_output) {
             output.push(grid_attempt.grid.clone());
         }
         output
     }
 
+    /// Answers which are not placed in the best grid found so far. Returns an empty list
+    /// if no complete grids have been generated yet.
+    pub fn unplaced_in_best(&self) -> Vec<String> {
+        match self.best_attempts(1).first() {
+            Some(best) => best.grid.unplaced_answers(),
+            None => vec![],
+        }
+    }
+
     fn get_current_best_score(&self) -> isize {
         self.current_generation_complete.iter().map(|x| x.summary_score).max().unwrap_or(0)
     }
@@ -468,25 +1068,78 @@ impl CrosswordGenerator {
         stats::mean_of_hashmaps(all_move_counts)
     }
 
-    pub fn generate(&mut self) -> Vec<CrosswordGrid> {
-        let mut best_overall_score: isize = -1;
-        let mut best_score: isize = self.get_current_best_score();
-        let mut reached_convergence: bool = false;
-        let mut last_generation_stringified = self.stringified_output();
-        let mut rounds_no_increase = 0;
-        println!("Round {}. Current best score is {:?}", self.round, best_score);
+    /// Run the remaining rounds of generation and return the best grids. If no complete grids
+    /// were found, either returns an error or the best partially filled grid, depending on
+    /// the on-empty setting.
+    pub fn generate(&mut self) -> Result<Vec<CrosswordGrid>, GeneratorError> {
+        println!("Round {}. Current best score is {:?}", self.round, self.get_current_best_score());
+        self.run_rounds(self.settings.max_rounds);
 
-        while !reached_convergence && self.round < self.settings.max_rounds {
+        if self.reached_convergence {
+            println!("Stopped iterating since we stopped increasing our score");
+        }
+
+        match self.best_attempts(1).first() {
+            Some(best) => {
+                println!("Best final score is: {}", best.score);
+                let missing = self.missing_required_in_best();
+                if !missing.is_empty() {
+                    println!("Required answers missing from the best grid: {}", missing.join(", "));
+                }
+                Ok(self.output_best(self.settings.num_per_generation))
+            },
+            None => match self.settings.on_empty {
+                OnEmpty::Error => Err(GeneratorError::NoCompleteGrids),
+                OnEmpty::BestPartial => {
+                    println!("No complete grids found, so returning the best partial grid");
+                    self.best_partial().map(|grid| vec![grid]).ok_or(GeneratorError::NoCompleteGrids)
+                },
+            },
+        }
+    }
+
+    /// The highest scoring of the current ancestor grids, which may not be fully filled
+    fn best_partial(&self) -> Option<CrosswordGrid> {
+        self.current_generation_ancestors.iter()
+            .max_by_key(|grid_attempt| grid_attempt.summary_score)
+            .map(|grid_attempt| grid_attempt.grid.clone())
+    }
+
+    /// Keep the best grid of the current generation if it beats every grid seen so far
+    fn update_best_ever(&mut self) {
+        if !self.settings.keep_best_ever {
+            return;
+        }
+        let best = self.best_attempts(1).first().map(|grid_attempt| (*grid_attempt).clone());
+        if let Some(best) = best {
+            let improved = self.best_ever.as_ref()
+                .is_none_or(|best_ever| best.score.summary > best_ever.score.summary);
+            if improved {
+                self.best_ever = Some(best);
+            }
+        }
+    }
+
+    /// Run at most num_rounds further rounds of generation, stopping early if we converge
+    /// or reach the maximum number of rounds. Returns the number of rounds run.
+    pub fn run_rounds(&mut self, num_rounds: usize) -> usize {
+        let mut rounds_run = 0;
+        while rounds_run < num_rounds && !self.reached_convergence && self.round < self.settings.max_rounds {
             self.next_generation();
-            best_score = self.get_current_best_score();
-            println!("Round {}. Average score is {}", self.round, self.get_average_scores());
-            println!("Round {}. Current best score is {:?}", self.round, best_score);
-            println!("Round {}. Move counts of best is {}", self.round, custom_hashmap_format(&self.current_generation_complete[0].move_counts,
-                                                                                           "best_count",
-                                                                                           ":: "));
-            println!("Round {}. Average move counts {}", self.round, custom_hashmap_format(&self.average_move_counts(),
-                                                                                        "average_count",
-                                                                                        ":: "));
+            self.update_best_ever();
+            let best_score = self.get_current_best_score();
+            if let Some(best) = self.current_generation_complete.first() {
+                println!("Round {}. Average score is {}", self.round, self.get_average_scores());
+                println!("Round {}. Current best score is {:?}", self.round, best_score);
+                println!("Round {}. Move counts of best is {}", self.round, custom_hashmap_format(&best.move_counts,
+                                                                                               "best_count",
+                                                                                               ":: "));
+                println!("Round {}. Average move counts {}", self.round, custom_hashmap_format(&self.average_move_counts(),
+                                                                                            "average_count",
+                                                                                            ":: "));
+            } else {
+                println!("Round {}. No complete grids found", self.round);
+            }
             println!("Round {}. Average move counts {}", self.round, custom_hashmap_format(&self.average_move_counts_ancestors(),
                                                                                         "average_ancestor_count",
                                                                                         ":: "));
@@ -495,31 +1148,48 @@ impl CrosswordGenerator {
             info!("This generation:\n{}", this_generation_stringified);
             if self.round > self.settings.min_rounds {
                 info!("Checking for convergence");
-                if best_score <= best_overall_score {
-                    rounds_no_increase += 1;
+                if best_score <= self.best_overall_score {
+                    self.rounds_no_increase += 1;
                 } else {
-                    rounds_no_increase = 0;
-                    best_overall_score = best_score;
+                    self.rounds_no_increase = 0;
+                    self.best_overall_score = best_score;
                 }
             }
-            if rounds_no_increase > 5 && this_generation_stringified == last_generation_stringified {
-                reached_convergence = true;
+            if self.rounds_no_increase > 5 && this_generation_stringified == self.last_generation_stringified {
+                self.reached_convergence = true;
             }
-            last_generation_stringified = this_generation_stringified;
+            self.last_generation_stringified = this_generation_stringified;
             self.round += 1;
+            rounds_run += 1;
         }
-        if reached_convergence {
-            println!("Stopped iterating since we stopped increasing our score");
-        }
-
-        println!("Best final score is: {}", self.current_generation_complete[0].score);
-        self.output_best(self.settings.num_per_generation)
+        rounds_run
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grid::CrosswordGridBuilder;
+
+    /// Words which can all be placed together in one grid
+    const EXAMPLE_WORDS: [&str; 10] = ["BEARER", "ABOVE", "HERE", "BANANA", "ROYAL", "ROE", "NEVER", "INVALUABLE", "BOO", "AVERAGE"];
+
+    /// A smaller set of words which fit together, including AROUND
+    const EXAMPLE_WORDS_WITH_AROUND: [&str; 8] = ["BEARER", "ABOVE", "HERE", "INVALUABLE", "BANANA", "ROYAL", "AROUND", "ROE"];
+
+    /// A generator small enough for quick tests, with four grids per generation and four
+    /// children for each, stopping after max_rounds. The extra settings are applied last,
+    /// so can override these.
+    fn small_generator(words: &[&str], max_rounds: usize, extra: &[(&str, usize)]) -> CrosswordGenerator {
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("num-per-gen", 4);
+        settings_map.insert("num-children", 4);
+        settings_map.insert("max-rounds", max_rounds);
+        for (name, value) in extra.iter() {
+            settings_map.insert(name, *value);
+        }
+        CrosswordGenerator::new_from_singletons(words.to_vec(), settings_map)
+    }
 
     #[test]
     fn test_first_generation() {
@@ -528,12 +1198,588 @@ mod tests {
         debug!("{:#?}", generator);
     }
 
+    #[test]
+    fn test_parse_dictionary_line() {
+        assert_eq!(parse_dictionary_line("APPLE 1200"), ("APPLE", 1200));
+        assert_eq!(parse_dictionary_line("APPLE"), ("APPLE", 1));
+        assert_eq!(parse_dictionary_line("BELLY FLOP\t3 "), ("BELLY FLOP", 3));
+        assert_eq!(parse_dictionary_line("BELLY FLOP::clue"), ("BELLY FLOP::clue", 1));
+    }
+
+    #[test]
+    fn test_seed_from_str() {
+        let seed = CrosswordGeneratorSettings::seed_from_str("spring-2024");
+        assert_eq!(CrosswordGeneratorSettings::seed_from_str("spring-2024"), seed);
+        assert_ne!(CrosswordGeneratorSettings::seed_from_str("spring-2025"), seed);
+
+        // Known FNV-1a values, so the seed for a given name never changes
+        assert_eq!(CrosswordGeneratorSettings::seed_from_str(""), 0xcbf29ce484222325);
+        assert_eq!(CrosswordGeneratorSettings::seed_from_str("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_new_from_dictionary() {
+        crate::logging::init_logger(true);
+        let generator = CrosswordGenerator::new_from_dictionary("tests/resources/dictionary.txt", 10, HashMap::new()).unwrap();
+        assert_eq!(generator.current_generation_ancestors.len(), 10);
+
+        // The dictionary has ten very frequent words, which should be the ones chosen
+        let mut chosen: Vec<String> = generator.current_generation_ancestors.iter()
+            .map(|attempt| attempt.grid.to_string().trim().replace("\n", ""))
+            .collect();
+        chosen.sort();
+        assert_eq!(chosen, vec!["APPLE", "BANANA", "CHERRY", "GRAPE", "LEMON",
+                                "MANGO", "MELON", "ORANGE", "PEACH", "PLUM"]);
+
+        let generator2 = CrosswordGenerator::new_from_dictionary("tests/resources/dictionary.txt", 10, HashMap::new()).unwrap();
+        assert_eq!(generator.stringified_output(), generator2.stringified_output());
+    }
+
+    #[test]
+    fn test_file_with_header() {
+        crate::logging::init_logger(true);
+        let contents = "# title: Fruit salad\n# Author: A. Setter\n\nAPPLE::Fruit (5)::\nPEAR::Another fruit (4)::\n";
+        let mut generator = CrosswordGenerator::new_from_file_contents(contents, HashMap::new());
+        assert_eq!(generator.answers(), vec!["APPLE", "PEAR"]);
+        for attempt in generator.current_generation_ancestors.iter() {
+            assert_eq!(attempt.grid.meta().title(), Some("Fruit salad"));
+            assert_eq!(attempt.grid.meta().author(), Some("A. Setter"));
+        }
+
+        let grids = generator.generate().unwrap();
+        assert!(!grids.is_empty());
+        assert_eq!(grids[0].meta().title(), Some("Fruit salad"));
+        let json = serde_json::to_string(&grids[0]).unwrap();
+        assert!(json.contains("Fruit salad"));
+
+        let generator = CrosswordGenerator::new_from_file_contents("APPLE\nPEAR\n", HashMap::new());
+        assert!(generator.current_generation_ancestors[0].grid.meta().is_empty());
+    }
+
+    #[test]
+    fn test_input_fingerprint() {
+        crate::logging::init_logger(true);
+        let generator = CrosswordGenerator::new_from_singletons(vec!["APPLE", "PEAR", "BANANA"], HashMap::new());
+        let fingerprint = generator.input_fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+
+        let reordered = CrosswordGenerator::new_from_singletons(vec!["BANANA", "APPLE", "PEAR"], HashMap::new());
+        assert_eq!(reordered.input_fingerprint(), fingerprint);
+
+        let other_words = CrosswordGenerator::new_from_singletons(vec!["APPLE", "PEAR", "PLUM"], HashMap::new());
+        assert_ne!(other_words.input_fingerprint(), fingerprint);
+
+        let mut settings_map = HashMap::new();
+        settings_map.insert("seed", 14);
+        let other_settings = CrosswordGenerator::new_from_singletons(vec!["APPLE", "PEAR", "BANANA"], settings_map);
+        assert_ne!(other_settings.input_fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_estimate_workload() {
+        crate::logging::init_logger(true);
+        let words = vec!["APPLE", "PEAR", "BANANA", "JUJU"];
+        let generator = CrosswordGenerator::new_from_singletons(words.clone(), HashMap::new());
+        let workload = generator.estimate_workload();
+        assert_eq!(workload.num_words, words.len());
+        assert_eq!(workload.total_letters, 19);
+        assert_eq!(workload.num_singletons, 1);
+        assert!(workload.difficulty > 0.0 && workload.difficulty < 1.0);
+
+        let easier = CrosswordGenerator::new_from_singletons(vec!["APPLE", "PEAR", "BANANA"], HashMap::new());
+        assert_eq!(easier.estimate_workload().num_singletons, 0);
+        assert!(easier.estimate_workload().difficulty < workload.difficulty);
+    }
+
+    #[test]
+    fn test_enumeration_style() {
+        crate::logging::init_logger(true);
+        let generator = CrosswordGenerator::new_from_singletons(vec!["BELLY FLOP::Bad dive"], HashMap::new());
+        let csv = generator.current_generation_ancestors[0].grid.clues_to_csv();
+        assert!(csv.contains("Bad dive (5,4)"));
+
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("us-enumeration", 1);
+        let generator = CrosswordGenerator::new_from_singletons(vec!["BELLY FLOP::Bad dive"], settings_map);
+        let csv = generator.current_generation_ancestors[0].grid.clues_to_csv();
+        assert!(csv.contains("Bad dive (9)"));
+    }
+
+    #[test]
+    fn test_score_no_placed_words() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.add_unplaced_word("BETA", "", None);
+        grid.unplace_word(0);
+        assert_eq!(grid.count_placed_words(), 0);
+
+        let score = CrosswordGridScore::new(&grid, &CrosswordGeneratorSettings::default());
+        assert_eq!(score.summary, 0.0);
+        assert_eq!(score.words_unplaced, 2.0);
+        // NaN would be serialized as null, so each field must be a valid number
+        let fields = serde_json::to_value(score).unwrap();
+        for (field, value) in fields.as_object().unwrap().iter() {
+            assert!(value.as_f64().is_some_and(|v| !v.is_nan()), "Field {} is not a number: {}", field, value);
+        }
+    }
+
+    #[test]
+    fn test_score_single_word() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        assert_eq!(grid.scoring_dimensions(), (1, 5));
+        let score = CrosswordGridScore::new(&grid, &CrosswordGeneratorSettings::default());
+        assert_eq!(score.total_cells, 5.0);
+        assert_eq!(score.proportion_filled, 1.0);
+
+        // Extra empty space around the grid doesn't change the score
+        grid.fit_to_size_with_buffer(3);
+        assert_eq!(grid.scoring_dimensions(), (1, 5));
+        let score = CrosswordGridScore::new(&grid, &CrosswordGeneratorSettings::default());
+        assert_eq!(score.proportion_filled, 1.0);
+        assert_eq!(score.non_square_penalty, 20.0);
+    }
+
+    #[test]
+    fn test_normalized_score() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let raw_settings = CrosswordGeneratorSettings::default();
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("normalize-scores", 1);
+        let normalized_settings = CrosswordGeneratorSettings::new_from_hashmap(settings_map);
+
+        let raw = CrosswordGridScore::new(&grid, &raw_settings);
+        let normalized = CrosswordGridScore::new(&grid, &normalized_settings);
+        assert_eq!(raw.summary, CrosswordGridScore::new_raw(&grid, &raw_settings).summary);
+        assert_eq!(normalized.summary, CrosswordGridScore::new_normalized(&grid, &raw_settings).summary);
+
+        // The components are the same, but the cycles and intersections no longer dominate
+        assert_eq!(raw.num_cycles, normalized.num_cycles);
+        assert_eq!(raw.num_intersections, normalized.num_intersections);
+        assert!(normalized.summary < raw.summary);
+
+        // Each component is at most 1, so the summary is bounded by the sum of the weights
+        let total_weight = (10 + 500 + 1000 + 100 + 5000 + 10) as f64;
+        assert!(normalized.summary > 0.0 && normalized.summary <= total_weight);
+    }
+
+    #[test]
+    fn test_edge_words_score() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("weight-edge-words", 10);
+        let score = CrosswordGridScore::new(&grid, &CrosswordGeneratorSettings::new_from_hashmap(settings_map));
+        let unweighted_score = CrosswordGridScore::new(&grid, &CrosswordGeneratorSettings::default());
+        assert_eq!(score.edge_words, 7.0);
+        assert_eq!(unweighted_score.edge_words, 7.0);
+        assert!((unweighted_score.summary - score.summary - 70.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_intersection_centrality_score() {
+        crate::logging::init_logger(true);
+        let central = CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+        let at_ends = CrosswordGridBuilder::new().from_string("ALE\nL  \nE  \n");
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("weight-intersection-centrality", 100);
+        let settings = CrosswordGeneratorSettings::new_from_hashmap(settings_map);
+
+        let default_settings = CrosswordGeneratorSettings::default();
+        assert_eq!(CrosswordGridScore::new(&central, &default_settings).summary,
+                   CrosswordGridScore::new(&at_ends, &default_settings).summary);
+        assert_eq!(CrosswordGridScore::new(&central, &settings).summary,
+                   CrosswordGridScore::new(&at_ends, &settings).summary + 100.0);
+    }
+
+    #[test]
+    fn test_spine_penalty_score() {
+        crate::logging::init_logger(true);
+        let stringy = CrosswordGridBuilder::new().from_string("CAT\nA  \nRAT\n  O\n  E\n");
+        let woven = CrosswordGridBuilder::new().from_string("CAT\nA A\nNAP\n");
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("weight-spine-penalty", 100);
+        let settings = CrosswordGeneratorSettings::new_from_hashmap(settings_map);
+
+        let default_settings = CrosswordGeneratorSettings::default();
+        for grid in [&stringy, &woven].iter() {
+            let unpenalised = CrosswordGridScore::new(grid, &default_settings).summary;
+            let penalised = CrosswordGridScore::new(grid, &settings).summary;
+            assert!((unpenalised - penalised - 100.0 * grid.spine_ratio()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_prefer_long_words_forbidding_squares() {
+        crate::logging::init_logger(true);
+        let generator = small_generator(&["ALPHA"], 1, &[("prefer-long-words", 1), ("forbid-solid-squares", 1)]);
+
+        // HAPPY is the longest word, so is placed first
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        for word in ["PEA", "HAPPY", "LA", "PLAN"].iter() {
+            grid.add_unplaced_word(word, "", None);
+        }
+        assert!(generator.place_random_word(&mut grid, 13));
+        assert_eq!(grid.unplaced_answers(), vec!["PEA", "LA", "PLAN"]);
+
+        // The only placement for CD would complete a 2x2 block, so is still rejected
+        let mut grid = CrosswordGridBuilder::new().from_string("AB\nCD\n");
+        let cd_word_id = grid.cell_at(crate::grid::Location(1, 0)).unwrap().across_word_id.unwrap();
+        grid.unplace_word(cd_word_id);
+        assert!(!generator.place_random_word(&mut grid, 13));
+        assert_eq!(grid.count_placed_words(), 3);
+    }
+
+    #[test]
+    fn test_diversity_metrics() {
+        crate::logging::init_logger(true);
+        for metric in 0..3 {
+            let mut generator = small_generator(&EXAMPLE_WORDS_WITH_AROUND, 2, &[("diversity-metric", metric)]);
+            assert_eq!(generator.settings.diversity_metric, DiversityMetric::from_setting(metric));
+            generator.run_rounds(2);
+            assert_eq!(generator.current_generation_complete.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_hash_dedup() {
+        crate::logging::init_logger(true);
+        let mut generator = small_generator(&EXAMPLE_WORDS_WITH_AROUND, 2, &[]);
+        generator.run_rounds(2);
+
+        // Sample population containing several copies of some grids
+        let mut population: Vec<CrosswordGridAttempt> = vec![];
+        for attempt in generator.current_generation_ancestors.iter().chain(generator.current_generation_complete.iter()) {
+            population.push(attempt.clone());
+            population.push(attempt.clone());
+        }
+        population.extend(generator.current_generation_complete.iter().cloned());
+
+        let by_string: Vec<String> = generator.restrict_to_unique_by_string(population.clone())
+            .iter().map(|attempt| attempt.grid.to_string()).collect();
+        let by_hash: Vec<String> = generator.restrict_to_unique_by_hash(population.clone())
+            .iter().map(|attempt| attempt.grid.to_string()).collect();
+        assert!(by_string.len() < population.len());
+        assert_eq!(by_string, by_hash);
+
+        generator.settings.hash_dedup = true;
+        assert_eq!(generator.restrict_to_unique(population).len(), by_hash.len());
+    }
+
+    #[test]
+    fn test_grid_filter() {
+        crate::logging::init_logger(true);
+        let words = ["BEARER", "ABOVE", "HERE", "VALUABLE", "BANANA", "ROYAL", "AROUND", "ROE", "NEVER"];
+
+        let mut unfiltered = small_generator(&words, 3, &[]);
+        unfiltered.run_rounds(3);
+        assert!(unfiltered.current_generation_complete.iter().any(|a| a.grid.get_grid_dimensions().1 > 8));
+
+        let mut generator = small_generator(&words, 3, &[]);
+        generator.set_grid_filter(|grid| grid.get_grid_dimensions().1 <= 8);
+        generator.run_rounds(3);
+        assert!(!generator.current_generation_complete.is_empty());
+        for attempt in generator.current_generation_ancestors.iter().chain(generator.current_generation_complete.iter()) {
+            assert!(attempt.grid.get_grid_dimensions().1 <= 8, "Grid too wide\n{}", attempt.grid.to_string());
+        }
+    }
+
+    #[test]
+    fn test_on_empty() {
+        crate::logging::init_logger(true);
+        // XYZ shares no letters with the other words, so no grid can place every word
+        let words = ["APPLE", "PEAR", "BANANA", "XYZ"];
+        let mut generator = small_generator(&words, 2, &[("num-per-gen", 3), ("num-children", 3)]);
+        generator.set_grid_filter(|grid| grid.count_unplaced_words() == 0);
+        assert_matches!(generator.generate(), Err(GeneratorError::NoCompleteGrids));
+
+        let mut generator = small_generator(&words, 2, &[("num-per-gen", 3), ("num-children", 3), ("on-empty", 1)]);
+        generator.set_grid_filter(|grid| grid.count_unplaced_words() == 0);
+        let results = generator.generate().unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].count_placed_words() > 0);
+        assert!(results[0].count_unplaced_words() > 0);
+    }
+
+    #[test]
+    fn test_unplaced_in_best() {
+        crate::logging::init_logger(true);
+        // QUIZ shares no letters with any other word, so can never be placed with them
+        let mut generator = small_generator(&["BEARER", "ABOVE", "HERE", "BANANA", "ROYAL", "ROE", "NEVER", "QUIZ"], 3, &[]);
+        assert_eq!(generator.unplaced_in_best(), Vec::<String>::new());
+
+        let results = generator.generate().unwrap();
+        let unplaced = generator.unplaced_in_best();
+        assert!(unplaced.contains(&"QUIZ".to_string()));
+        assert_eq!(unplaced, results[0].unplaced_answers());
+        assert_eq!(unplaced.len(), results[0].count_unplaced_words());
+    }
+
+    #[test]
+    fn test_output_ordered_by_score() {
+        crate::logging::init_logger(true);
+        let mut generator = small_generator(&EXAMPLE_WORDS, 3, &[("num-per-gen", 6)]);
+        let results = generator.generate().unwrap();
+        assert!(results.len() > 1);
+
+        let scores: Vec<f64> = results.iter().map(|grid| generator.settings.score_summary(grid)).collect();
+        assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]), "Scores out of order: {:?}", scores);
+        for (grid, next_grid) in results.iter().zip(results.iter().skip(1)) {
+            if generator.settings.score_summary(grid) == generator.settings.score_summary(next_grid) {
+                assert!(grid.canonical_string() < next_grid.canonical_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_require_all() {
+        crate::logging::init_logger(true);
+        let mut words = EXAMPLE_WORDS.to_vec();
+        words.push("TSK");
+
+        let mut generator = small_generator(&words, 4, &[]);
+        generator.require_all(&["average", "INVALUABLE"]);
+        assert_eq!(generator.settings.required_answers, vec!["AVERAGE", "INVALUABLE"]);
+        let results = generator.generate().unwrap();
+        assert!(!results.is_empty());
+        for grid in results.iter() {
+            let unplaced = grid.unplaced_answers();
+            assert!(!unplaced.contains(&"AVERAGE".to_string()));
+            assert!(!unplaced.contains(&"INVALUABLE".to_string()));
+        }
+        assert_eq!(generator.missing_required_in_best(), Vec::<String>::new());
+
+        // TSK shares no letters with any other word, so the best grid is output without it
+        let mut generator = small_generator(&words, 4, &[]);
+        generator.require_all(&["AVERAGE", "TSK"]);
+        let results = generator.generate().unwrap();
+        assert!(!results.is_empty());
+        assert_eq!(generator.missing_required_in_best(), vec!["TSK"]);
+        assert!(results[0].unplaced_answers().contains(&"TSK".to_string()));
+    }
+
+    #[test]
+    fn test_add_words() {
+        crate::logging::init_logger(true);
+        // Leave out AVERAGE, to be added later
+        let mut generator = small_generator(&EXAMPLE_WORDS[..9], 4, &[]);
+        generator.generate().unwrap();
+        assert_eq!(generator.round, 4);
+
+        // BEARER is already in the word list, so only AVERAGE is added
+        generator.add_words(vec!["AVERAGE::Typical (7)", "bearer"]);
+        assert_eq!(generator.round, 0);
+        assert!(generator.current_generation_complete.is_empty());
+        for attempt in generator.current_generation_ancestors.iter() {
+            assert_eq!(attempt.grid.count_all_words(), 10);
+            assert!(attempt.grid.unplaced_answers().contains(&"AVERAGE".to_string()));
+        }
+
+        let results = generator.generate().unwrap();
+        assert!(!results.is_empty());
+        for grid in results.iter() {
+            assert!(grid.unplaced_answers().is_empty());
+            assert!(grid.all_answers().contains(&"AVERAGE".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_move_log_replay() {
+        crate::logging::init_logger(true);
+        // Leave out AVERAGE, to be added later
+        let words = &EXAMPLE_WORDS[..9];
+
+        let mut generator = small_generator(words, 3, &[]);
+        generator.generate().unwrap();
+        assert!(generator.output_move_logs().iter().all(|log| log.is_none()));
+
+        let mut generator = small_generator(words, 3, &[("record-move-log", 1)]);
+        generator.run_rounds(2);
+        generator.add_words(vec!["AVERAGE"]);
+        let results = generator.generate().unwrap();
+        let logs = generator.output_move_logs();
+        assert_eq!(logs.len(), results.len());
+        for (grid, log) in results.iter().zip(logs.iter()) {
+            let log = log.as_ref().unwrap();
+            assert_eq!(log.seed_grid.count_placed_words(), 1);
+            assert!(log.moves.iter().any(|applied_move| matches!(applied_move, AppliedMove::AddWords { .. })));
+            let replayed = generator.replay_move_log(log);
+            assert_eq!(replayed.to_string(), grid.to_string());
+            assert_eq!(replayed.all_answers(), grid.all_answers());
+        }
+    }
+
+    #[test]
+    fn test_keep_best_ever() {
+        crate::logging::init_logger(true);
+        let mut generator = small_generator(&EXAMPLE_WORDS, 5, &[("num-per-gen", 3), ("num-children", 3), ("keep-best-ever", 1)]);
+        let mut best_round_score = f64::MIN;
+        while generator.run_rounds(1) == 1 {
+            if let Some(best) = generator.current_generation_complete.iter()
+                .map(|grid_attempt| grid_attempt.score.summary)
+                .max_by(|a, b| a.partial_cmp(b).unwrap()) {
+                best_round_score = best_round_score.max(best);
+            }
+            assert!(generator.best_ever.as_ref().unwrap().score.summary >= best_round_score);
+        }
+
+        let results = generator.generate().unwrap();
+        let best_ever = generator.best_ever.as_ref().unwrap();
+        assert_eq!(best_ever.score.summary, best_round_score);
+        assert_eq!(generator.best_attempts(1)[0].score.summary, best_round_score);
+        assert_eq!(results[0].to_string(), best_ever.grid.to_string());
+    }
+
+    #[test]
+    fn test_keep_best_ever_empty_final_generation() {
+        crate::logging::init_logger(true);
+        let mut generator = small_generator(&EXAMPLE_WORDS, 3, &[("num-per-gen", 3), ("num-children", 3), ("keep-best-ever", 1)]);
+        generator.run_rounds(2);
+        let best_ever = generator.best_ever.clone().unwrap();
+
+        // Every grid of the final round is rejected, but the best grid seen earlier is still output
+        generator.set_grid_filter(|_grid| false);
+        generator.current_generation_complete.clear();
+        let results = generator.generate().unwrap();
+        assert!(generator.current_generation_complete.is_empty());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].to_string(), best_ever.grid.to_string());
+    }
+
+    #[test]
+    fn test_require_min_cycles() {
+        crate::logging::init_logger(true);
+        let mut generator = small_generator(&EXAMPLE_WORDS, 4, &[("require-min-cycles", 1)]);
+        let results = generator.generate().unwrap();
+        assert!(!results.is_empty());
+        for grid in results.iter() {
+            assert!(grid.to_graph().count_cycles() >= 1);
+        }
+
+        // If no grids qualify then the best are still output
+        let mut generator = small_generator(&EXAMPLE_WORDS, 4, &[("require-min-cycles", 100)]);
+        let results = generator.generate().unwrap();
+        assert_eq!(results.len(), generator.current_generation_complete.len().min(4));
+        assert!(!results.is_empty());
+    }
+
+    #[test]
+    fn test_target_black_fraction() {
+        crate::logging::init_logger(true);
+        let target = 0.8;
+        let mean_deviation = |grids: &[CrosswordGrid]| {
+            grids.iter()
+                .map(|grid| {
+                    let (nrows, ncols) = grid.get_grid_dimensions();
+                    let black_fraction = 1.0 - grid.count_filled_cells() as f64 / (nrows * ncols) as f64;
+                    (black_fraction - target).abs()
+                })
+                .sum::<f64>() / grids.len() as f64
+        };
+
+        let mut generator = small_generator(&EXAMPLE_WORDS, 4, &[]);
+        let untargeted = generator.generate().unwrap();
+
+        let mut generator = small_generator(&EXAMPLE_WORDS, 4, &[("target-black-fraction", 80), ("weight-black-fraction", 100000)]);
+        let targeted = generator.generate().unwrap();
+
+        assert!(mean_deviation(&targeted) < mean_deviation(&untargeted));
+        assert!(mean_deviation(&targeted) < 0.05);
+    }
+
+    #[test]
+    fn test_merge_population() {
+        crate::logging::init_logger(true);
+        let words = &EXAMPLE_WORDS[..7];
+        let mut generator = small_generator(words, 2, &[("num-children", 3)]);
+        generator.generate().unwrap();
+
+        let mut other = small_generator(words, 2, &[("num-children", 3), ("seed", 99)]);
+        other.generate().unwrap();
+        let best_score = cmp::max(generator.get_current_best_score(), other.get_current_best_score());
+
+        generator.merge_population(other).unwrap();
+        assert!(generator.current_generation_complete.len() <= 4);
+        assert!(generator.current_generation_ancestors.len() <= 4);
+        assert!(!generator.current_generation_complete.is_empty());
+        assert_eq!(generator.get_current_best_score(), best_score);
+
+        let incompatible = CrosswordGenerator::new_from_singletons(vec!["APPLE", "PEAR"], HashMap::new());
+        assert_eq!(generator.merge_population(incompatible), Err(GeneratorError::IncompatibleWordLists));
+    }
+
+    #[test]
+    fn test_diversity_weight() {
+        crate::logging::init_logger(true);
+        let mut generator = small_generator(&EXAMPLE_WORDS_WITH_AROUND, 2, &[("num-per-gen", 6), ("diversity-weight", 0)]);
+        assert_eq!(generator.settings.diversity_weight, 0.0);
+        generator.run_rounds(2);
+
+        let population: Vec<CrosswordGridAttempt> = generator.current_generation_ancestors.iter()
+            .chain(generator.current_generation_complete.iter())
+            .cloned()
+            .collect();
+        let mut expected: Vec<isize> = generator.restrict_to_unique(population.clone()).iter().map(|a| a.summary_score).collect();
+        expected.sort_by(|a, b| b.cmp(a));
+        expected.truncate(5);
+
+        // With no diversity pressure, the picks are simply the highest scoring grids
+        let picked: Vec<isize> = generator.pick_best_varied(population, 5).iter().map(|a| a.summary_score).collect();
+        assert_eq!(picked, expected);
+    }
+
+    #[test]
+    fn test_checkpoint_errors() {
+        crate::logging::init_logger(true);
+        let checkpoint_path = std::env::temp_dir()
+            .join(format!("crossword_test_checkpoint_errors_{}.json", std::process::id()));
+        let checkpoint_path = checkpoint_path.to_str().unwrap();
+        assert_matches!(CrosswordGenerator::resume_from_checkpoint(checkpoint_path), Err(GeneratorError::Checkpoint(_)));
+
+        fs::write(checkpoint_path, "not a checkpoint").unwrap();
+        let resumed = CrosswordGenerator::resume_from_checkpoint(checkpoint_path);
+        let _ = fs::remove_file(checkpoint_path);
+        assert_matches!(resumed, Err(GeneratorError::Checkpoint(_)));
+
+        let generator = CrosswordGenerator::new_from_singletons(vec!["ALPHA", "BRAVO"], HashMap::new());
+        let missing_dir = std::env::temp_dir().join("crossword_no_such_dir").join("checkpoint.json");
+        assert_matches!(generator.save_checkpoint(missing_dir.to_str().unwrap()), Err(GeneratorError::Checkpoint(_)));
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        crate::logging::init_logger(true);
+        let mut generator = small_generator(&EXAMPLE_WORDS_WITH_AROUND, 6, &[]);
+        assert_eq!(generator.run_rounds(3), 3);
+        let checkpoint_path = std::env::temp_dir()
+            .join(format!("crossword_test_resume_from_checkpoint_{}.json", std::process::id()));
+        let checkpoint_path = checkpoint_path.to_str().unwrap();
+        let saved = generator.save_checkpoint(checkpoint_path);
+        let resumed = CrosswordGenerator::resume_from_checkpoint(checkpoint_path);
+        let _ = fs::remove_file(checkpoint_path);
+        saved.unwrap();
+        let mut resumed = resumed.unwrap();
+        assert_eq!(resumed.round, 3);
+        assert_eq!(resumed.stringified_output(), generator.stringified_output());
+        let resumed_results = resumed.generate().unwrap();
+
+        let mut uninterrupted = small_generator(&EXAMPLE_WORDS_WITH_AROUND, 6, &[]);
+        let uninterrupted_results = uninterrupted.generate().unwrap();
+
+        assert_eq!(resumed.round, uninterrupted.round);
+        assert_eq!(resumed_results.len(), uninterrupted_results.len());
+        for (resumed_grid, uninterrupted_grid) in resumed_results.iter().zip(uninterrupted_results.iter()) {
+            assert_eq!(resumed_grid.to_string(), uninterrupted_grid.to_string());
+            assert_eq!(resumed_grid.clues_to_csv(), uninterrupted_grid.clues_to_csv());
+        }
+    }
+
     #[test]
     #[ignore]
     fn test_next_generation() {
         crate::logging::init_logger(true);
-        let words = vec!["BEARER", "ABOVE", "HERE", "INVALUABLE", "BANANA", "ROYAL", "AROUND", "ROE"];
-        let mut generator = CrosswordGenerator::new_from_singletons(words, HashMap::new());
+        let mut generator = CrosswordGenerator::new_from_singletons(EXAMPLE_WORDS_WITH_AROUND.to_vec(), HashMap::new());
         generator.next_generation();
         generator.next_generation();
         generator.next_generation();
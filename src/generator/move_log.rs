@@ -0,0 +1,82 @@
+use serde::{Deserialize,Serialize};
+
+use crate::grid::CrosswordGrid;
+use crate::grid::Word;
+
+use super::CrosswordGenerator;
+
+/// A single move applied to a grid while generating, with enough detail to apply it again
+#[derive(Clone,Debug,Deserialize,Serialize)]
+pub enum AppliedMove {
+    /// Placed a random word, chosen using the seed
+    PlaceWord { seed: u64 },
+    /// Removed a random leaf word, chosen using the seed
+    PruneLeaves { seed: u64 },
+    /// Split the grid in two using the seed, keeping either the grid itself or the other half
+    Partition { seed: u64, kept_other_half: bool },
+    /// Merged with another grid. The other grid was built separately, so is kept in full.
+    Recombination { other: CrosswordGrid, min_overlaps: usize },
+    /// Added words, given in the clue file format, using CrosswordGenerator::add_words
+    AddWords { words: Vec<String> },
+}
+
+/// The grid a generated grid started from, and every move applied to it since, in order.
+/// Only recorded if the record-move-log setting is used.
+#[derive(Clone,Debug,Deserialize,Serialize)]
+pub struct MoveLog {
+    pub seed_grid: CrosswordGrid,
+    pub moves: Vec<AppliedMove>,
+}
+
+impl MoveLog {
+    pub fn new(seed_grid: CrosswordGrid) -> Self {
+        MoveLog {
+            seed_grid,
+            moves: vec![],
+        }
+    }
+}
+
+impl CrosswordGenerator {
+    /// Apply the moves in the log to its seed grid again, using the word placement strategy
+    /// from these settings. With the settings the log was recorded with, this rebuilds the
+    /// grid exactly.
+    pub fn replay_move_log(&self, log: &MoveLog) -> CrosswordGrid {
+        let mut grid = log.seed_grid.clone();
+        for applied_move in log.moves.iter() {
+            match applied_move {
+                AppliedMove::PlaceWord { seed } => {
+                    self.place_random_word(&mut grid, *seed);
+                },
+                AppliedMove::PruneLeaves { seed } => {
+                    grid.remove_random_leaves(1, *seed);
+                },
+                AppliedMove::Partition { seed, kept_other_half } => {
+                    let other_half = grid.random_partition(*seed);
+                    if *kept_other_half {
+                        grid = other_half;
+                    }
+                },
+                AppliedMove::Recombination { other, min_overlaps } => {
+                    grid.try_merge_with_grid(other, *min_overlaps);
+                },
+                AppliedMove::AddWords { words } => {
+                    for word_str in words.iter() {
+                        if let Ok(word) = Word::new_parsed_with_style(word_str, self.settings.enumeration_style) {
+                            grid.add_parsed_unplaced_word(word);
+                        }
+                    }
+                },
+            }
+        }
+        grid
+    }
+
+    /// Move logs of the grids that generate would output, in the same order. Each entry is
+    /// None unless the record-move-log setting was used.
+    pub fn output_move_logs(&self) -> Vec<Option<MoveLog>> {
+        self.best_attempts(self.settings.num_per_generation).iter()
+            .map(|grid_attempt| grid_attempt.move_log.clone())
+            .collect()
+    }
+}
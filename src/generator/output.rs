@@ -0,0 +1,126 @@
+use serde::{Deserialize,Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::grid::CrosswordPrinter;
+
+use super::CrosswordGenerator;
+
+/// File format used when writing generated grids to disk
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum OutputFormat {
+    /// The full grid, including clues, as JSON
+    Json,
+    /// The grid and clues in the ipuz format, for loading into other crossword software
+    Ipuz,
+    /// An SVG image of the blank grid with clue numbers
+    Svg,
+    /// The letters of the grid as plain text
+    Txt,
+    /// The clues of the placed words as CSV
+    Csv,
+    /// A LaTeX document for printing the blank grid and clues
+    Latex,
+}
+
+impl OutputFormat {
+    fn extension(&self) -> &str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Ipuz => "ipuz",
+            OutputFormat::Svg => "svg",
+            OutputFormat::Txt => "txt",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Latex => "tex",
+        }
+    }
+}
+
+/// Index of the grids written by write_results, in order from best to worst
+#[derive(Clone,Debug,Deserialize,Serialize)]
+pub struct Manifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+#[derive(Clone,Debug,Deserialize,Serialize)]
+pub struct ManifestEntry {
+    pub filename: String,
+    pub summary_score: isize,
+    pub words_placed: usize,
+    pub words_unplaced: usize,
+}
+
+impl CrosswordGenerator {
+    /// Write each of the best grids of the current generation to its own file in the directory,
+    /// named grid_0, grid_1 etc. with the extension of the format, along with a manifest.json
+    /// listing the files and their scores. The directory is created if it doesn't exist.
+    pub fn write_results(&self, dir: &str, format: OutputFormat) -> Result<Manifest, io::Error> {
+        fs::create_dir_all(dir)?;
+
+        let mut entries: Vec<ManifestEntry> = vec![];
+        for (index, grid_attempt) in self.best_attempts(self.settings.num_per_generation).iter().enumerate() {
+            let grid = &grid_attempt.grid;
+            let contents = match format {
+                OutputFormat::Json => serde_json::to_string(grid)?,
+                OutputFormat::Ipuz => grid.to_ipuz(),
+                OutputFormat::Svg => grid.to_svg(),
+                OutputFormat::Txt => grid.to_string(),
+                OutputFormat::Csv => grid.clues_to_csv(),
+                OutputFormat::Latex => CrosswordPrinter::new_default(grid.clone()).print(),
+            };
+            let filename = format!("grid_{}.{}", index, format.extension());
+            fs::write(Path::new(dir).join(&filename), contents)?;
+
+            entries.push(ManifestEntry {
+                filename,
+                summary_score: grid_attempt.summary_score,
+                words_placed: grid.count_placed_words(),
+                words_unplaced: grid.count_unplaced_words(),
+            });
+        }
+
+        let manifest = Manifest { entries };
+        fs::write(Path::new(dir).join("manifest.json"), serde_json::to_string_pretty(&manifest)?)?;
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_write_results() {
+        crate::logging::init_logger(true);
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("num-per-gen", 3);
+        settings_map.insert("num-children", 3);
+        settings_map.insert("max-rounds", 2);
+        let mut generator = CrosswordGenerator::new_from_singletons(vec!["APPLE", "PEAR", "BANANA", "ORANGE"], settings_map);
+        let results = generator.generate().unwrap();
+
+        let dir = std::env::temp_dir().join(format!("crossword_test_write_results_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let dir = dir.to_str().unwrap();
+        let manifest = generator.write_results(dir, OutputFormat::Txt).unwrap();
+        assert_eq!(manifest.entries.len(), results.len());
+        assert_eq!(manifest.entries[0].filename, "grid_0.txt");
+        assert_eq!(fs::read_to_string(Path::new(dir).join("grid_0.txt")).unwrap(), results[0].to_string());
+
+        let written: Manifest = serde_json::from_str(&fs::read_to_string(Path::new(dir).join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(written.entries.len(), results.len());
+
+        let manifest = generator.write_results(dir, OutputFormat::Json).unwrap();
+        assert_eq!(manifest.entries[1].filename, "grid_1.json");
+        assert!(Path::new(dir).join("grid_1.json").exists());
+
+        generator.write_results(dir, OutputFormat::Ipuz).unwrap();
+        assert_eq!(fs::read_to_string(Path::new(dir).join("grid_0.ipuz")).unwrap(), results[0].to_ipuz());
+        generator.write_results(dir, OutputFormat::Svg).unwrap();
+        assert_eq!(fs::read_to_string(Path::new(dir).join("grid_0.svg")).unwrap(), results[0].to_svg());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -7,6 +7,7 @@ use rand::rngs::StdRng;
 use super::CrosswordGridAttempt;
 use super::CrosswordGenerator;
 use super::MoveType;
+use super::AppliedMove;
 
 impl CrosswordGenerator {
     fn generate_partitions(&self,
@@ -50,6 +51,9 @@ impl CrosswordGenerator {
                           second.grid.to_string(),
                           first.grid.to_string());
                     first.increment_move_count(MoveType::Recombination);
+                    if self.settings.record_move_log {
+                        first.record_move(AppliedMove::Recombination { other: second.grid.clone(), min_overlaps });
+                    }
                     recombined.push(first);
                     min_overlaps += 1;
                 }
@@ -61,6 +65,7 @@ impl CrosswordGenerator {
             first_index += 1;
         }
 
+        recombined.retain(|attempt| self.passes_grid_filter(&attempt.grid));
         self.current_generation_ancestors.append(&mut recombined);
     }
 }
@@ -0,0 +1,226 @@
+use crate::grid::EnumerationStyle;
+
+use super::{CrosswordGeneratorSettings,DiversityMetric,OnEmpty};
+
+/// Typed alternative to building CrosswordGeneratorSettings from a hashmap of setting names.
+/// Any setting which isn't set keeps the same default as in new_from_hashmap.
+pub struct CrosswordGeneratorSettingsBuilder {
+    settings: CrosswordGeneratorSettings,
+}
+
+impl Default for CrosswordGeneratorSettingsBuilder {
+    fn default() -> Self {
+        CrosswordGeneratorSettingsBuilder::new()
+    }
+}
+
+impl CrosswordGeneratorSettingsBuilder {
+    pub fn new() -> Self {
+        CrosswordGeneratorSettingsBuilder {
+            settings: CrosswordGeneratorSettings::default(),
+        }
+    }
+
+    pub fn build(&self) -> CrosswordGeneratorSettings {
+        self.settings.clone()
+    }
+
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.settings.seed = seed;
+        self
+    }
+
+    pub fn moves_between_scores(&mut self, moves_between_scores: usize) -> &mut Self {
+        self.settings.moves_between_scores = moves_between_scores;
+        self
+    }
+
+    pub fn num_children(&mut self, num_children: usize) -> &mut Self {
+        self.settings.num_children = num_children;
+        self
+    }
+
+    pub fn num_partitions(&mut self, num_partitions: usize) -> &mut Self {
+        self.settings.num_partitions = num_partitions;
+        self
+    }
+
+    pub fn num_per_generation(&mut self, num_per_generation: usize) -> &mut Self {
+        self.settings.num_per_generation = num_per_generation;
+        self
+    }
+
+    pub fn max_rounds(&mut self, max_rounds: usize) -> &mut Self {
+        self.settings.max_rounds = max_rounds;
+        self
+    }
+
+    pub fn min_rounds(&mut self, min_rounds: usize) -> &mut Self {
+        self.settings.min_rounds = min_rounds;
+        self
+    }
+
+    pub fn weight_non_square(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_non_square = weight;
+        self
+    }
+
+    pub fn weight_prop_filled(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_prop_filled = weight;
+        self
+    }
+
+    pub fn weight_prop_intersect(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_prop_intersect = weight;
+        self
+    }
+
+    pub fn weight_num_cycles(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_num_cycles = weight;
+        self
+    }
+
+    pub fn weight_num_intersect(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_num_intersect = weight;
+        self
+    }
+
+    pub fn weight_avg_intersect(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_avg_intersect = weight;
+        self
+    }
+
+    pub fn weight_words_placed(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_words_placed = weight;
+        self
+    }
+
+    pub fn weight_compactness(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_compactness = weight;
+        self
+    }
+
+    pub fn weight_black_fraction(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_black_fraction = weight;
+        self
+    }
+
+    pub fn weight_edge_words(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_edge_words = weight;
+        self
+    }
+
+    pub fn weight_intersection_centrality(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_intersection_centrality = weight;
+        self
+    }
+
+    pub fn weight_spine_penalty(&mut self, weight: usize) -> &mut Self {
+        self.settings.weight_spine_penalty = weight;
+        self
+    }
+
+    /// Target fraction of black cells, between 0 and 1
+    pub fn target_black_fraction(&mut self, fraction: f64) -> &mut Self {
+        self.settings.target_black_fraction = Some(fraction);
+        self
+    }
+
+    pub fn enumeration_style(&mut self, enumeration_style: EnumerationStyle) -> &mut Self {
+        self.settings.enumeration_style = enumeration_style;
+        self
+    }
+
+    pub fn diversity_metric(&mut self, diversity_metric: DiversityMetric) -> &mut Self {
+        self.settings.diversity_metric = diversity_metric;
+        self
+    }
+
+    pub fn hash_dedup(&mut self, hash_dedup: bool) -> &mut Self {
+        self.settings.hash_dedup = hash_dedup;
+        self
+    }
+
+    /// How much of the similarity penalty to apply when picking varied grids, where 1 is the full penalty
+    pub fn diversity_weight(&mut self, diversity_weight: f64) -> &mut Self {
+        self.settings.diversity_weight = diversity_weight;
+        self
+    }
+
+    pub fn normalize_scores(&mut self, normalize_scores: bool) -> &mut Self {
+        self.settings.normalize_scores = normalize_scores;
+        self
+    }
+
+    pub fn require_min_cycles(&mut self, min_cycles: usize) -> &mut Self {
+        self.settings.require_min_cycles = min_cycles;
+        self
+    }
+
+    pub fn forbid_solid_squares(&mut self, forbid_solid_squares: bool) -> &mut Self {
+        self.settings.forbid_solid_squares = forbid_solid_squares;
+        self
+    }
+
+    pub fn prefer_long_words(&mut self, prefer_long_words: bool) -> &mut Self {
+        self.settings.prefer_long_words = prefer_long_words;
+        self
+    }
+
+    /// Always include the highest scoring grid from any round in the output
+    pub fn keep_best_ever(&mut self, keep_best_ever: bool) -> &mut Self {
+        self.settings.keep_best_ever = keep_best_ever;
+        self
+    }
+
+    /// Record every move applied to each grid, so that it can be replayed
+    pub fn record_move_log(&mut self, record_move_log: bool) -> &mut Self {
+        self.settings.record_move_log = record_move_log;
+        self
+    }
+
+    /// Answers which must be placed, which should already be sanitised and uppercase
+    pub fn required_answers(&mut self, required_answers: &[&str]) -> &mut Self {
+        self.settings.required_answers = required_answers.iter().map(|answer| answer.to_string()).collect();
+        self
+    }
+
+    pub fn on_empty(&mut self, on_empty: OnEmpty) -> &mut Self {
+        self.settings.on_empty = on_empty;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_settings_builder() {
+        assert_eq!(CrosswordGeneratorSettingsBuilder::new().build(), CrosswordGeneratorSettings::default());
+
+        let settings = CrosswordGeneratorSettingsBuilder::new()
+            .seed(99)
+            .num_children(3)
+            .num_per_generation(5)
+            .weight_num_cycles(2000)
+            .target_black_fraction(0.25)
+            .diversity_metric(DiversityMetric::Jaccard)
+            .hash_dedup(true)
+            .on_empty(OnEmpty::BestPartial)
+            .build();
+
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        settings_map.insert("seed", 99);
+        settings_map.insert("num-children", 3);
+        settings_map.insert("num-per-gen", 5);
+        settings_map.insert("weight-num-cycles", 2000);
+        settings_map.insert("target-black-fraction", 25);
+        settings_map.insert("diversity-metric", 1);
+        settings_map.insert("hash-dedup", 1);
+        settings_map.insert("on-empty", 1);
+        assert_eq!(settings, CrosswordGeneratorSettings::new_from_hashmap(settings_map));
+        assert_ne!(settings, CrosswordGeneratorSettings::default());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize,Serialize};
+use std::collections::HashSet;
+
+use ndarray::Array2;
+
+use crate::grid::CrosswordGrid;
+
+/// How to measure the similarity of grids when picking a varied set of the best grids
+#[derive(Clone,Copy,Debug,Eq,PartialEq,Deserialize,Serialize)]
+pub enum DiversityMetric {
+    /// Overlap of the adjacency matrices of the word graphs
+    AdjacencyProduct,
+    /// Jaccard index of the sets of intersecting word pairs
+    Jaccard,
+    /// Only penalise grids which are identical once any empty border is ignored
+    CanonicalDedup,
+}
+
+impl DiversityMetric {
+    /// Convert from the numeric setting used on the command line
+    pub fn from_setting(setting: usize) -> Self {
+        match setting {
+            1 => DiversityMetric::Jaccard,
+            2 => DiversityMetric::CanonicalDedup,
+            _ => DiversityMetric::AdjacencyProduct,
+        }
+    }
+}
+
+/// A measure of similarity between grids, from 0 (nothing in common) to 1 (identical).
+/// Features are calculated once per grid, since each grid is compared many times.
+pub(super) trait GridSimilarity {
+    type Features;
+
+    fn features(grid: &CrosswordGrid) -> Self::Features;
+
+    fn similarity(first: &Self::Features, second: &Self::Features) -> f64;
+}
+
+pub(super) struct AdjacencyProduct;
+
+impl GridSimilarity for AdjacencyProduct {
+    type Features = Array2<u8>;
+
+    fn features(grid: &CrosswordGrid) -> Self::Features {
+        grid.to_graph_adjacency_matrix()
+    }
+
+    fn similarity(adj1: &Self::Features, adj2: &Self::Features) -> f64 {
+        let union = (adj1 + adj2).iter().filter(|x| **x > 0).count() as f64;
+        let intersection = (adj1 * adj2).sum() as f64;
+        let similarity = intersection / union;
+        similarity
+    }
+}
+
+pub(super) struct Jaccard;
+
+impl GridSimilarity for Jaccard {
+    type Features = HashSet<(usize, usize)>;
+
+    fn features(grid: &CrosswordGrid) -> Self::Features {
+        grid.get_all_intersections().into_iter().collect()
+    }
+
+    fn similarity(first: &Self::Features, second: &Self::Features) -> f64 {
+        let union = first.union(second).count();
+        if union == 0 {
+            // Neither grid has any intersections, so they have identical edge sets
+            1.0
+        } else {
+            first.intersection(second).count() as f64 / union as f64
+        }
+    }
+}
+
+pub(super) struct CanonicalDedup;
+
+impl GridSimilarity for CanonicalDedup {
+    type Features = String;
+
+    fn features(grid: &CrosswordGrid) -> Self::Features {
+        // The same definition of identical grids as used by CrosswordGrid::puzzle_id
+        grid.canonical_string()
+    }
+
+    fn similarity(first: &Self::Features, second: &Self::Features) -> f64 {
+        if first == second { 1.0 } else { 0.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::CrosswordGridBuilder;
+
+    #[test]
+    fn test_jaccard_matches_adjacency() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let mut other_grid = grid.clone();
+        let (_number, last_word_id, _direction) = *grid.clue_numbering().last().unwrap();
+        other_grid.unplace_word(last_word_id);
+
+        for (first, second) in [(&grid, &grid), (&grid, &other_grid), (&other_grid, &grid)].iter() {
+            let adjacency_similarity = AdjacencyProduct::similarity(&AdjacencyProduct::features(first),
+                                                                    &AdjacencyProduct::features(second));
+            let jaccard_similarity = Jaccard::similarity(&Jaccard::features(first),
+                                                         &Jaccard::features(second));
+            assert_eq!(adjacency_similarity, jaccard_similarity);
+        }
+        assert_eq!(Jaccard::similarity(&Jaccard::features(&grid), &Jaccard::features(&grid)), 1.0);
+        assert!(Jaccard::similarity(&Jaccard::features(&grid), &Jaccard::features(&other_grid)) < 1.0);
+    }
+
+    #[test]
+    fn test_canonical_dedup() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let mut other_grid = grid.clone();
+        let (_number, last_word_id, _direction) = *grid.clue_numbering().last().unwrap();
+        other_grid.unplace_word(last_word_id);
+
+        let features = CanonicalDedup::features(&grid);
+        assert_eq!(features.lines().next(), Some("  BEARER  "));
+        assert_eq!(CanonicalDedup::similarity(&features, &CanonicalDedup::features(&grid.clone())), 1.0);
+        assert_eq!(CanonicalDedup::similarity(&features, &CanonicalDedup::features(&other_grid)), 0.0);
+    }
+
+    #[test]
+    fn test_from_setting() {
+        assert_eq!(DiversityMetric::from_setting(0), DiversityMetric::AdjacencyProduct);
+        assert_eq!(DiversityMetric::from_setting(1), DiversityMetric::Jaccard);
+        assert_eq!(DiversityMetric::from_setting(2), DiversityMetric::CanonicalDedup);
+    }
+}
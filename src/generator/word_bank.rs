@@ -0,0 +1,89 @@
+use log::warn;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+use crate::grid::{CrosswordGrid,EnumerationStyle,Word};
+
+use super::{CrosswordGenerator,CrosswordGeneratorSettings};
+
+/// The parsed words of a clue file, which can be shared between many generators without
+/// reading and parsing the file again for each one e.g. when making a batch of puzzles.
+#[derive(Clone,Debug)]
+pub struct CrosswordWordBank {
+    words: Vec<Word>,
+    enumeration_style: EnumerationStyle,
+}
+
+impl CrosswordWordBank {
+    /// Parse each line of the clue file, adding word lengths to clues in the UK style
+    pub fn from_file(filename: &str) -> Result<Self, io::Error> {
+        CrosswordWordBank::from_file_with_style(filename, EnumerationStyle::Uk)
+    }
+
+    /// Parse each line of the clue file, adding word lengths to clues in the given style.
+    /// Lines which can't be parsed are skipped.
+    pub fn from_file_with_style(filename: &str, enumeration_style: EnumerationStyle) -> Result<Self, io::Error> {
+        let contents = fs::read_to_string(filename)?;
+        let words: Vec<Word> = contents.lines()
+            .filter_map(|line| Word::new_parsed_with_style(line, enumeration_style).ok())
+            .collect();
+        Ok(CrosswordWordBank {
+            words,
+            enumeration_style,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl CrosswordGenerator {
+    /// Create a generator using the words of the bank at the given indices, in that order.
+    /// Indices which are out of range for the bank are skipped.
+    pub fn new_from_bank(bank: &CrosswordWordBank, subset: &[usize], settings_map: HashMap<&str, usize>) -> Self {
+        let settings = CrosswordGeneratorSettings::new_from_hashmap(settings_map);
+        if settings.enumeration_style != bank.enumeration_style {
+            warn!("Word bank was parsed with enumeration style {:?}, not {:?} as in the settings",
+                  bank.enumeration_style, settings.enumeration_style);
+        }
+
+        let words: Vec<(usize, Word)> = subset.iter()
+            .filter_map(|index| bank.words.get(*index).cloned())
+            .enumerate()
+            .collect();
+        let grids = CrosswordGrid::random_singleton_grids_from_words(words, settings.seed);
+        CrosswordGenerator::new_from_singleton_grids(grids, settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_from_bank() {
+        crate::logging::init_logger(true);
+        let bank = CrosswordWordBank::from_file("tests/resources/input_with_clues.txt").unwrap();
+        assert_eq!(bank.len(), 5);
+        assert!(CrosswordWordBank::from_file("tests/resources/no_such_file.txt").is_err());
+
+        let first = CrosswordGenerator::new_from_bank(&bank, &[0, 1, 2, 3], HashMap::new());
+        let second = CrosswordGenerator::new_from_bank(&bank, &[3, 4, 0, 100], HashMap::new());
+        assert_eq!(first.current_generation_ancestors.len(), 4);
+        assert_eq!(second.current_generation_ancestors.len(), 3);
+        assert_eq!(first.current_generation_ancestors[0].grid.all_answers(), vec!["SALESMAN", "EARTRUMPET", "SONNET", "ANOXIC"]);
+        assert_eq!(second.current_generation_ancestors[0].grid.all_answers(), vec!["ANOXIC", "OLID", "SALESMAN"]);
+
+        // Parsing up front gives the same words as parsing the file for each generator
+        let lines: Vec<String> = fs::read_to_string("tests/resources/input_with_clues.txt").unwrap()
+            .lines().take(4).map(|line| line.to_string()).collect();
+        let from_file = CrosswordGenerator::new_from_singletons(lines.iter().map(|line| line.as_str()).collect(), HashMap::new());
+        assert_eq!(from_file.current_generation_ancestors[0].grid.to_string(), first.current_generation_ancestors[0].grid.to_string());
+    }
+}
@@ -1,5 +1,6 @@
 use log::{info,warn,debug};
 use std::collections::{HashSet,HashMap,VecDeque};
+use std::cmp;
 
 use crate::utils::Counter;
 
@@ -188,6 +189,152 @@ impl Graph {
         self.count_edges() + 1 - self.count_nodes()
     }
 
+    /// Returns a fundamental set of cycles in the graph: one cycle for each edge which is not
+    /// in a breadth-first spanning forest of the graph. Each cycle is given as the list of
+    /// nodes around it, so its length is the number of edges in the cycle.
+    ///
+    /// For a connected graph there are count_cycles() of these cycles. The cycles found are
+    /// deterministic.
+    ///
+    /// ```
+    /// let graph = crossword::graph::Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 3), (3, 0), (2, 4), (4, 3)]);
+    /// assert_eq!(graph.fundamental_cycles(), vec![vec![2, 1, 0, 3], vec![2, 1, 0, 3, 4]]);
+    /// ```
+    pub fn fundamental_cycles(&self) -> Vec<Vec<usize>> {
+        let mut parents: HashMap<usize, usize> = HashMap::new();
+        let mut depths: HashMap<usize, usize> = HashMap::new();
+        let mut tree_edges: HashSet<Edge> = HashSet::new();
+
+        // Build a breadth-first spanning tree for each connected component
+        let mut node_ids: Vec<usize> = self.node_map.keys().cloned().collect();
+        node_ids.sort();
+        for root in node_ids {
+            if !depths.contains_key(&root) {
+                depths.insert(root, 0);
+                let mut node_queue: VecDeque<usize> = VecDeque::new();
+                node_queue.push_back(root);
+
+                while let Some(node_id) = node_queue.pop_front() {
+                    let edges = self._get_edge_list(node_id).expect("Node should exist, as we found it in the node_map or through an edge");
+                    for Edge(_, neighbour_id) in edges {
+                        if !depths.contains_key(&neighbour_id) {
+                            depths.insert(neighbour_id, depths[&node_id] + 1);
+                            parents.insert(neighbour_id, node_id);
+                            tree_edges.insert(Edge(node_id, neighbour_id));
+                            tree_edges.insert(Edge(neighbour_id, node_id));
+                            node_queue.push_back(neighbour_id);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Each edge not in the spanning forest closes a cycle with the path between
+        // its two ends in the tree
+        let mut non_tree_edges: Vec<Edge> = vec![];
+        for node in self.node_storage.iter() {
+            for neighbour_id in node.connected_nodes.iter() {
+                let edge = Edge(node.node_id, *neighbour_id);
+                if node.node_id < *neighbour_id && !tree_edges.contains(&edge) {
+                    non_tree_edges.push(edge);
+                }
+            }
+        }
+        non_tree_edges.sort();
+
+        let mut cycles: Vec<Vec<usize>> = vec![];
+        for Edge(first, second) in non_tree_edges {
+            let mut first_path: Vec<usize> = vec![first];
+            let mut second_path: Vec<usize> = vec![second];
+            let mut first_ancestor = first;
+            let mut second_ancestor = second;
+            while depths[&first_ancestor] > depths[&second_ancestor] {
+                first_ancestor = parents[&first_ancestor];
+                first_path.push(first_ancestor);
+            }
+            while depths[&second_ancestor] > depths[&first_ancestor] {
+                second_ancestor = parents[&second_ancestor];
+                second_path.push(second_ancestor);
+            }
+            while first_ancestor != second_ancestor {
+                first_ancestor = parents[&first_ancestor];
+                first_path.push(first_ancestor);
+                second_ancestor = parents[&second_ancestor];
+                second_path.push(second_ancestor);
+            }
+
+            // Both paths end at the common ancestor, so only include it once
+            second_path.pop();
+            second_path.reverse();
+            first_path.append(&mut second_path);
+            cycles.push(first_path);
+        }
+        cycles
+    }
+
+    /// Scores the cycles in the graph, weighting each fundamental cycle by the inverse
+    /// of its length, so that short, tightly interlocking cycles count for more than
+    /// long meandering ones.
+    ///
+    /// ```
+    /// let triangle = crossword::graph::Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 0)]);
+    /// let long_loop = crossword::graph::Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+    /// assert_eq!(triangle.count_cycles(), long_loop.count_cycles());
+    /// assert_eq!(triangle.weighted_cycle_score(), 1.0 / 3.0);
+    /// assert_eq!(long_loop.weighted_cycle_score(), 1.0 / 6.0);
+    /// ```
+    pub fn weighted_cycle_score(&self) -> f64 {
+        self.fundamental_cycles().iter().map(|cycle| 1.0 / (cycle.len() as f64)).sum()
+    }
+
+    /// Returns the eccentricity of the node, the length of the longest shortest path from it
+    /// to any node it can reach. Returns an error if the node isn't present.
+    ///
+    /// ```
+    /// let graph = crossword::graph::Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 3)]);
+    /// assert_eq!(graph.eccentricity(0).unwrap(), 3);
+    /// assert_eq!(graph.eccentricity(1).unwrap(), 2);
+    /// assert!(graph.eccentricity(4).is_err());
+    /// ```
+    pub fn eccentricity(&self, node_id: usize) -> Result<usize, GraphError> {
+        self.get_node(node_id)?;
+        let mut depths: HashMap<usize, usize> = HashMap::new();
+        depths.insert(node_id, 0);
+        let mut node_queue: VecDeque<usize> = VecDeque::new();
+        node_queue.push_back(node_id);
+
+        let mut max_depth = 0;
+        while let Some(current_id) = node_queue.pop_front() {
+            let depth = depths[&current_id];
+            max_depth = cmp::max(max_depth, depth);
+            for neighbour_id in self.get_node(current_id)?.connected_nodes.iter() {
+                if !depths.contains_key(neighbour_id) {
+                    depths.insert(*neighbour_id, depth + 1);
+                    node_queue.push_back(*neighbour_id);
+                }
+            }
+        }
+        Ok(max_depth)
+    }
+
+    /// Returns the diameter of the graph, the number of edges in the longest shortest path
+    /// between any two nodes which are connected. Returns 0 for an empty graph.
+    ///
+    /// ```
+    /// let graph = crossword::graph::Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 3)]);
+    /// assert_eq!(graph.longest_path_len(), 3);
+    ///
+    /// // Closing the path into a cycle brings the ends together
+    /// let graph = crossword::graph::Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 3), (3, 0)]);
+    /// assert_eq!(graph.longest_path_len(), 2);
+    /// ```
+    pub fn longest_path_len(&self) -> usize {
+        self.node_map.keys()
+            .map(|node_id| self.eccentricity(*node_id).expect("Node id should be present - found in the node_map"))
+            .max()
+            .unwrap_or(0)
+    }
+
     /// Returns a list of all leaves in the graph i.e. nodes connected to at most one other node.
     ///
     /// These nodes can be safely removed from the graph without increasing the number
@@ -516,6 +663,33 @@ mod tests {
         assert!(!graph.is_connected());
     }
 
+    #[test]
+    fn test_fundamental_cycles() {
+        crate::logging::init_logger(true);
+        let graph = Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(graph.fundamental_cycles(), Vec::<Vec<usize>>::new());
+        assert_eq!(graph.weighted_cycle_score(), 0.0);
+
+        let graph = Graph::new_from_edges(vec![(0, 1), (1, 2), (2, 0), (5, 3), (3, 4), (4, 5)]);
+        assert_eq!(graph.fundamental_cycles(), vec![vec![1, 0, 2], vec![4, 3, 5]]);
+
+        let mut edges: Vec<(usize, usize)> = vec![];
+        for i in 0..20 {
+            edges.push((i, i + 1));
+            edges.push((i, (i + 7) % 21));
+        }
+        let graph = Graph::new_from_edges(edges);
+        let cycles = graph.fundamental_cycles();
+        assert_eq!(cycles.len(), graph.count_cycles());
+        for cycle in cycles.iter() {
+            // Each consecutive pair of nodes in the cycle (including last to first) is an edge
+            for i in 0..cycle.len() {
+                let node = graph.get_node(cycle[i]).unwrap();
+                assert!(node.connected_nodes.contains(&cycle[(i + 1) % cycle.len()]));
+            }
+        }
+    }
+
     #[test]
     fn test_partition_nodes() {
         crate::logging::init_logger(true);
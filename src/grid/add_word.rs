@@ -164,7 +164,7 @@ mod tests {
         grid.place_word_in_cell(Location(3, 0), innards_word_id, 0, Direction::Across)?;
         grid.check_valid();
 
-        let mut from_file = CrosswordGridBuilder::new().from_file("tests/resources/built_up.txt");
+        let mut from_file = CrosswordGridBuilder::new().from_file("tests/resources/built_up.txt").unwrap();
         from_file.fit_to_size();
         debug!("{}", grid.to_string());
         assert_eq!(from_file.to_string(), grid.to_string());
@@ -175,7 +175,7 @@ mod tests {
     #[test]
     fn test_add_word_to_grid_adjacent() {
         crate::logging::init_logger(true);
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt").unwrap();
         let button_word_id = grid.add_unplaced_word("BUTTON", "", None);
         grid.check_valid();
         let before_failure = grid.to_string();
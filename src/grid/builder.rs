@@ -3,12 +3,14 @@ use log::debug;
 use std::collections::HashMap;
 
 use std::fs;
+use std::io;
 
 use super::CrosswordGrid;
 use super::Cell;
 use super::Location;
 use super::Direction;
 use super::Word;
+use super::PuzzleMeta;
 
 pub struct CrosswordGridBuilder {
     cell_map: HashMap<Location, Cell>,
@@ -21,6 +23,7 @@ pub struct CrosswordGridBuilder {
     index: usize,
     word_index: usize,
     last_location: Location,
+    blank_char: char,
 }
 
 impl CrosswordGridBuilder {
@@ -36,16 +39,33 @@ impl CrosswordGridBuilder {
             max_col: 0,
             word_index: 0,
             last_location: Location(0, 0),
+            blank_char: ' ',
         }
     }
 
-    pub fn from_file(&mut self, filename: &str) -> CrosswordGrid {
-        let contents = fs::read_to_string(filename).expect("Unable to read file");
+    /// Treat the given character as an empty cell, as well as ' ', e.g. for files
+    /// using '.' or '#' to mark the gaps between words.
+    pub fn with_blank_char(&mut self, blank_char: char) -> &mut Self {
+        self.blank_char = blank_char;
+        self
+    }
+
+    /// Build the grid from the contents of the file, returning an error if it can't be read
+    pub fn from_file(&mut self, filename: &str) -> Result<CrosswordGrid, io::Error> {
+        let contents = fs::read_to_string(filename)?;
         debug!("File contents: {}", contents);
-        self.from_string(&contents)
+        Ok(self.from_string(&contents))
     }
 
     pub fn from_string(&mut self, string: &str) -> CrosswordGrid {
+        let grid = self.build_unchecked(string);
+        grid.check_valid();
+        grid
+    }
+
+    /// Build the grid from the string, without checking that the result is valid
+    /// e.g. that it is connected.
+    pub(super) fn build_unchecked(&mut self, string: &str) -> CrosswordGrid {
         let characters: Vec<char> = string.chars().collect();
 
         for c in characters {
@@ -63,7 +83,7 @@ impl CrosswordGridBuilder {
                 let location = Location(self.row, self.col);
                 self.last_location = location;
 
-                if c == ' ' {
+                if c == ' ' || c == self.blank_char {
                     // End any existing words we have
                     self.current_across_word_id = None;
                     self.current_down_word_ids.insert(self.col, None);
@@ -101,6 +121,7 @@ impl CrosswordGridBuilder {
             word_map: self.word_map.clone(),
             top_left_cell_index: Location(0, 0),
             bottom_right_cell_index: self.last_location,
+            meta: PuzzleMeta::default(),
         };
 
         let mut singleton_word_ids: Vec<usize> = vec![];
@@ -114,7 +135,6 @@ impl CrosswordGridBuilder {
             grid.delete_word(word_id);
         }
 
-        grid.check_valid();
         grid.fit_to_size();
         grid.fill_black_cells();
         grid
@@ -1,9 +1,10 @@
 use std::fmt;
+use serde::{Deserialize,Serialize};
 
 use super::Direction;
 use super::CellError;
 
-#[derive(Clone,Copy,Debug)]
+#[derive(Clone,Copy,Debug,Deserialize,Serialize)]
 enum FillStatus {
     Filled(FilledCell),
     // Nothing known about cell
@@ -12,7 +13,7 @@ enum FillStatus {
     Black,
 }
 
-#[derive(Clone,Copy)]
+#[derive(Clone,Copy,Deserialize,Serialize)]
 struct FilledCell {
     letter: char,
     across_word_id: Option<usize>,
@@ -35,7 +36,16 @@ impl FilledCell {
     }
 }
 
-#[derive(Clone,Copy)]
+/// Read-only view of a cell, for inspecting the grid from outside the crate
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct PublicCell {
+    pub letter: Option<char>,
+    pub is_black: bool,
+    pub across_word_id: Option<usize>,
+    pub down_word_id: Option<usize>,
+}
+
+#[derive(Clone,Copy,Deserialize,Serialize)]
 pub(super) struct Cell {
     fill_status: FillStatus,
 }
@@ -53,6 +63,15 @@ impl Cell {
         }
     }
 
+    pub fn public_view(&self) -> PublicCell {
+        PublicCell {
+            letter: if self.contains_letter() { Some(self.to_char()) } else { None },
+            is_black: self.is_black(),
+            across_word_id: self.get_across_word_id(),
+            down_word_id: self.get_down_word_id(),
+        }
+    }
+
     pub fn empty() -> Self {
         Cell {
             fill_status: FillStatus::Empty,
@@ -178,6 +197,14 @@ impl Cell {
         }
     }
 
+    /// Change the letter in a filled cell, keeping the words it belongs to.
+    /// Cells without a letter are left unchanged.
+    pub fn set_letter(&mut self, letter: char) {
+        if let FillStatus::Filled(filled_cell) = &mut self.fill_status {
+            filled_cell.letter = letter;
+        }
+    }
+
     pub fn set_empty(&mut self) {
         self.fill_status = FillStatus::Empty;
     }
@@ -0,0 +1,542 @@
+use serde::{Deserialize,Serialize};
+use serde_json::{Value,json};
+use std::collections::{BTreeMap,HashMap};
+
+use super::CrosswordGrid;
+use super::CrosswordError;
+use super::Location;
+use super::Direction;
+use super::Cell;
+use super::Word;
+use super::PuzzleMeta;
+use super::VALID_ANSWERCHARS;
+
+/// Everything needed to resume work on a grid: every word, placed or not, with its clue,
+/// required direction and placement. The cells are rebuilt from the placements.
+#[derive(Deserialize,Serialize)]
+struct Workspace {
+    words: BTreeMap<usize, Word>,
+    #[serde(default)]
+    meta: PuzzleMeta,
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl CrosswordGrid {
+    /// Encode the letters of the grid as a short string which can be shared e.g. in a URL.
+    /// The code is the number of columns, then a '.', then the cells row by row, with each
+    /// run of cells without a letter replaced by its length. Clues are not included.
+    pub fn to_compact_code(&self) -> String {
+        let rows = self.to_char_grid();
+        let ncols = rows.first().map_or(0, |row| row.len());
+        let mut code = format!("{}.", ncols);
+        let mut blank_run: usize = 0;
+        for c in rows.iter().flatten() {
+            if *c == ' ' {
+                blank_run += 1;
+            } else {
+                if blank_run > 0 {
+                    code.push_str(&blank_run.to_string());
+                    blank_run = 0;
+                }
+                code.push(*c);
+            }
+        }
+        if blank_run > 0 {
+            code.push_str(&blank_run.to_string());
+        }
+        code
+    }
+
+    /// Build a grid from a code produced by to_compact_code
+    pub fn from_compact_code(code: &str) -> Result<Self, CrosswordError> {
+        let invalid = || CrosswordError::InvalidCompactCode(code.to_string());
+        let (ncols, cells_code) = code.split_once('.').ok_or_else(invalid)?;
+        let ncols: usize = ncols.parse().map_err(|_| invalid())?;
+        if ncols == 0 {
+            return Err(invalid());
+        }
+
+        let mut cells: Vec<char> = vec![];
+        let mut blank_run: usize = 0;
+        for c in cells_code.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                blank_run = blank_run * 10 + digit as usize;
+            } else if VALID_ANSWERCHARS.contains(c) {
+                cells.resize(cells.len() + blank_run, ' ');
+                blank_run = 0;
+                cells.push(c);
+            } else {
+                return Err(invalid());
+            }
+        }
+        cells.resize(cells.len() + blank_run, ' ');
+
+        if (cells.len() / ncols) * ncols != cells.len() {
+            return Err(invalid());
+        }
+        let rows: Vec<Vec<char>> = cells.chunks(ncols).map(|row| row.to_vec()).collect();
+        CrosswordGrid::from_char_grid(&rows, ' ')
+    }
+
+    /// Save the full state of the grid as JSON, including unplaced words and all clues,
+    /// to be loaded again by from_workspace_json
+    pub fn to_workspace_json(&self) -> String {
+        let workspace = Workspace {
+            words: self.word_map.iter().map(|(word_id, word)| (*word_id, word.clone())).collect(),
+            meta: self.meta.clone(),
+        };
+        serde_json::to_string_pretty(&workspace).expect("Workspace should always be serializable")
+    }
+
+    /// Load a grid saved by to_workspace_json. Returns an error if the JSON can't be parsed,
+    /// or if the placed words don't fit together into a valid grid.
+    pub fn from_workspace_json(json: &str) -> Result<Self, CrosswordError> {
+        let workspace: Workspace = serde_json::from_str(json).map_err(|error| CrosswordError::InvalidWorkspace(error.to_string()))?;
+        CrosswordGrid::from_placed_words(workspace.words, workspace.meta)
+    }
+
+    /// Build a grid from words which already know their placements, filling in the cells
+    /// and black cells. Returns an error if the placed words don't fit together.
+    pub(super) fn from_placed_words(words: BTreeMap<usize, Word>, meta: PuzzleMeta) -> Result<Self, CrosswordError> {
+        let mut placements: Vec<(usize, Location, Direction)> = vec![];
+        let mut word_map: HashMap<usize, Word> = HashMap::new();
+        for (word_id, mut word) in words.into_iter() {
+            if let Some((start_location, _end_location, direction)) = word.get_location() {
+                placements.push((word_id, start_location, direction));
+            }
+            word.remove_placement();
+            word_map.insert(word_id, word);
+        }
+
+        let mut cell_map: HashMap<Location, Cell> = HashMap::new();
+        cell_map.insert(Location(0, 0), Cell::empty());
+        let mut grid = CrosswordGrid {
+            cell_map,
+            word_map,
+            top_left_cell_index: Location(0, 0),
+            bottom_right_cell_index: Location(0, 0),
+            meta,
+        };
+        for (word_id, start_location, direction) in placements {
+            grid.no_check_place_word_in_cell(start_location, word_id, 0, direction)?;
+        }
+        grid.fit_to_size();
+        grid.fill_black_cells();
+        grid.check_all_word_placement_valid()?;
+        Ok(grid)
+    }
+
+    /// Number the placed words in the same way as a printed crossword: walking the cells
+    /// row by row, each cell that starts a new word gets the next clue number.
+    ///
+    /// Returns (clue_number, word_id, direction) ordered by clue number, with the across
+    /// word before the down word when a cell starts both.
+    pub fn clue_numbering(&self) -> Vec<(usize, usize, Direction)> {
+        let mut numbering: Vec<(usize, usize, Direction)> = vec![];
+        let mut last_start: Option<Location> = None;
+        let mut last_clue_number = 0;
+
+        for (word_id, direction) in self.words_in_reading_order() {
+            let (start, _end, _direction) = self.word_map.get(&word_id).unwrap().get_location().unwrap();
+            if last_start != Some(start) {
+                last_clue_number += 1;
+                last_start = Some(start);
+            }
+            numbering.push((last_clue_number, word_id, direction));
+        }
+        numbering
+    }
+
+    /// Clue numbers which head both an across and a down word, in ascending order
+    pub fn shared_numbers(&self) -> Vec<usize> {
+        let numbering = self.clue_numbering();
+        numbering.iter()
+            .filter(|(number, _word_id, direction)| {
+                *direction == Direction::Across
+                    && numbering.iter().any(|(other_number, _id, other_direction)| other_number == number && *other_direction == Direction::Down)
+            })
+            .map(|(number, _word_id, _direction)| *number)
+            .collect()
+    }
+
+    /// The placed words in the order a solver reading the grid row by row would reach them:
+    /// sorted by the row then column of their start cell, with across before down when
+    /// a cell starts both.
+    pub fn words_in_reading_order(&self) -> Vec<(usize, Direction)> {
+        let mut starts: Vec<(isize, isize, Direction, usize)> = self.word_map.iter()
+            .filter_map(|(word_id, word)| word.get_location().map(|(start, _end, direction)| (start.0, start.1, direction, *word_id)))
+            .collect();
+        starts.sort();
+        starts.into_iter().map(|(_row, _col, direction, word_id)| (word_id, direction)).collect()
+    }
+
+    /// Return the start of a placed word as (row, col, direction), with coordinates
+    /// counted from the top-left corner of the box containing the filled cells rather than
+    /// in the internal coordinate space. Returns None if the word doesn't exist or isn't placed.
+    pub fn normalized_placement(&self, word_id: usize) -> Option<(usize, usize, Direction)> {
+        let word = self.word_map.get(&word_id)?;
+        let (start_location, _end_location, direction) = word.get_location()?;
+        let (top_left, _bottom_right) = self.filled_bounding_box()?;
+        let row = start_location.0 - top_left.0;
+        let col = start_location.1 - top_left.1;
+        Some((row as usize, col as usize, direction))
+    }
+
+    /// Export the clues of all placed words as CSV, with columns
+    /// number,direction,answer,clue,length and rows in numbering order.
+    /// Answers are given with their punctuation e.g. O'CLOCK, as they would be displayed.
+    pub fn clues_to_csv(&self) -> String {
+        let mut csv = String::from("number,direction,answer,clue,length\n");
+        for (clue_number, word_id, direction) in self.clue_numbering() {
+            let word = self.word_map.get(&word_id).unwrap();
+            csv.push_str(&format!("{},{:?},{},{},{}\n",
+                                  clue_number,
+                                  direction,
+                                  csv_field(&word.display_text),
+                                  csv_field(&word.clue),
+                                  word.len()));
+        }
+        csv
+    }
+
+    /// The (clue_number, direction, answer, clue) of every placed word in numbering order,
+    /// for use as an answer key. Answers are given with their punctuation, as in clues_to_csv.
+    pub fn answer_key(&self) -> Vec<(usize, Direction, String, String)> {
+        self.clue_numbering().into_iter()
+            .map(|(clue_number, word_id, direction)| {
+                let word = self.word_map.get(&word_id).unwrap();
+                (clue_number, direction, word.display_text.clone(), word.clue.clone())
+            })
+            .collect()
+    }
+
+    /// The clue number starting at each cell which starts a placed word
+    fn clue_number_map(&self) -> HashMap<Location, usize> {
+        self.clue_numbering().into_iter()
+            .map(|(clue_number, word_id, _direction)| {
+                let (start, _end, _direction) = self.word_map.get(&word_id).unwrap().get_location().unwrap();
+                (start, clue_number)
+            })
+            .collect()
+    }
+
+    /// The locations of the cells in the playable area, row by row, as printed by to_string
+    fn playable_rows(&self) -> Vec<Vec<Location>> {
+        ((self.top_left_cell_index.0 + 1)..self.bottom_right_cell_index.0)
+            .map(|row| ((self.top_left_cell_index.1 + 1)..self.bottom_right_cell_index.1)
+                 .map(|col| Location(row, col))
+                 .collect())
+            .collect()
+    }
+
+    /// Export the grid in the ipuz format (http://ipuz.org), with the clue numbers, the
+    /// solution and the clues of all placed words. Empty cells are blocks, marked with '#'.
+    /// The title and author are included if they are set in the metadata.
+    pub fn to_ipuz(&self) -> String {
+        let numbers = self.clue_number_map();
+        let rows = self.playable_rows();
+        let puzzle: Vec<Vec<Value>> = rows.iter()
+            .map(|row| row.iter().map(|location| {
+                if self.cell_map.get(location).unwrap().contains_letter() {
+                    json!(numbers.get(location).cloned().unwrap_or(0))
+                } else {
+                    json!("#")
+                }
+            }).collect())
+            .collect();
+        let solution: Vec<Vec<String>> = rows.iter()
+            .map(|row| row.iter().map(|location| {
+                let cell = self.cell_map.get(location).unwrap();
+                if cell.contains_letter() {
+                    cell.to_char().to_string()
+                } else {
+                    "#".to_string()
+                }
+            }).collect())
+            .collect();
+
+        let mut across: Vec<Value> = vec![];
+        let mut down: Vec<Value> = vec![];
+        for (clue_number, direction, _answer, clue) in self.answer_key() {
+            match direction {
+                Direction::Across => across.push(json!([clue_number, clue])),
+                Direction::Down => down.push(json!([clue_number, clue])),
+            }
+        }
+
+        let mut ipuz = json!({
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": rows.first().map_or(0, |row| row.len()), "height": rows.len() },
+            "puzzle": puzzle,
+            "solution": solution,
+            "clues": { "Across": across, "Down": down },
+        });
+        if let Some(title) = self.meta.title() {
+            ipuz["title"] = json!(title);
+        }
+        if let Some(author) = self.meta.author() {
+            ipuz["author"] = json!(author);
+        }
+        serde_json::to_string_pretty(&ipuz).expect("ipuz should always be serializable")
+    }
+
+    /// Draw the blank grid as an SVG image, with empty cells shaded black and the clue
+    /// number in the corner of each cell which starts a word
+    pub fn to_svg(&self) -> String {
+        const CELL_SIZE: usize = 30;
+        let numbers = self.clue_number_map();
+        let rows = self.playable_rows();
+        let width = rows.first().map_or(0, |row| row.len()) * CELL_SIZE;
+        let height = rows.len() * CELL_SIZE;
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+                              width, height, width, height);
+        for (row_index, row) in rows.iter().enumerate() {
+            for (col_index, location) in row.iter().enumerate() {
+                let x = col_index * CELL_SIZE;
+                let y = row_index * CELL_SIZE;
+                let fill = if self.cell_map.get(location).unwrap().contains_letter() { "white" } else { "black" };
+                svg.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"black\"/>\n",
+                                      x, y, CELL_SIZE, CELL_SIZE, fill));
+                if let Some(clue_number) = numbers.get(location) {
+                    svg.push_str(&format!("  <text x=\"{}\" y=\"{}\" font-size=\"9\">{}</text>\n",
+                                          x + 2, y + 10, clue_number));
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::CrosswordGridBuilder;
+    use super::super::CrosswordError;
+    use super::super::EnumerationStyle;
+
+    fn parse_csv_line(line: &str) -> Vec<String> {
+        let mut fields: Vec<String> = vec![];
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if in_quotes && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                },
+                '"' => in_quotes = !in_quotes,
+                ',' if !in_quotes => fields.push(field.split_off(0)),
+                _ => field.push(c),
+            }
+        }
+        fields.push(field);
+        fields
+    }
+
+    #[test]
+    fn test_compact_code() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let code = grid.to_compact_code();
+        assert!(code.starts_with("10.2BEARER4O4O2ABOVE1HERE"));
+        assert!(code.len() < grid.to_string().len());
+
+        let decoded = CrosswordGrid::from_compact_code(&code).unwrap();
+        assert_eq!(decoded.to_string(), grid.to_string());
+        assert_eq!(decoded.to_compact_code(), code);
+
+        assert_matches!(CrosswordGrid::from_compact_code("BEARER"), Err(CrosswordError::InvalidCompactCode(_)));
+        assert_matches!(CrosswordGrid::from_compact_code("0.AB"), Err(CrosswordError::InvalidCompactCode(_)));
+        assert_matches!(CrosswordGrid::from_compact_code("4.AB1"), Err(CrosswordError::InvalidCompactCode(_)));
+        assert_matches!(CrosswordGrid::from_compact_code("2.A!"), Err(CrosswordError::InvalidCompactCode(_)));
+    }
+
+    #[test]
+    fn test_workspace_json() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let unplaced_word_id = grid.add_unplaced_word("UNPLACED", "Not yet in the grid (8)", Some(Direction::Down));
+        let mut meta = PuzzleMeta::default();
+        meta.insert("title", "Simple example");
+        grid.set_meta(meta);
+
+        let json = grid.to_workspace_json();
+        let loaded = CrosswordGrid::from_workspace_json(&json).unwrap();
+        assert_eq!(loaded.to_string(), grid.to_string());
+        assert_eq!(loaded.count_unplaced_words(), 1);
+        assert_eq!(loaded.count_placed_words(), grid.count_placed_words());
+        let unplaced = loaded.get_word(unplaced_word_id).unwrap();
+        assert_eq!(unplaced.clue, "Not yet in the grid (8)");
+        assert_eq!(unplaced.get_required_direction(), Some(Direction::Down));
+        assert_eq!(loaded.answer_key(), grid.answer_key());
+        assert_eq!(loaded.meta().title(), Some("Simple example"));
+        assert_eq!(loaded.to_workspace_json(), json);
+
+        assert_matches!(CrosswordGrid::from_workspace_json("not json"), Err(CrosswordError::InvalidWorkspace(_)));
+    }
+
+    #[test]
+    fn test_difficulty_exported() {
+        crate::logging::init_logger(true);
+        let grids = CrosswordGrid::random_singleton_grids(vec!["ALPHA::Letter::ACROSS::3", "BRAVO::Well done"],
+                                                          13, EnumerationStyle::Uk);
+        let json = grids[0].to_workspace_json();
+        assert!(json.contains("\"difficulty\": 3"));
+        assert!(json.contains("\"difficulty\": null"));
+        let loaded = CrosswordGrid::from_workspace_json(&json).unwrap();
+        assert_eq!(loaded.get_word(0).unwrap().difficulty, Some(3));
+        assert_eq!(loaded.get_word(1).unwrap().difficulty, None);
+        assert!(serde_json::to_string(&grids[0]).unwrap().contains("\"difficulty\":3"));
+    }
+
+    #[test]
+    fn test_ipuz_and_svg() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let (nrows, ncols) = (grid.to_char_grid().len(), grid.to_char_grid()[0].len());
+
+        let ipuz: serde_json::Value = serde_json::from_str(&grid.to_ipuz()).unwrap();
+        assert_eq!(ipuz["dimensions"]["width"], ncols);
+        assert_eq!(ipuz["dimensions"]["height"], nrows);
+        assert_eq!(ipuz["puzzle"][0][0], "#");
+        assert_eq!(ipuz["puzzle"][0][2], 1);
+        assert_eq!(ipuz["puzzle"][0][3], 0);
+        assert_eq!(ipuz["puzzle"][0][9], "#");
+        assert_eq!(ipuz["solution"][0][2], "B");
+        assert_eq!(ipuz["solution"][0][9], "#");
+        let clue_count = ipuz["clues"]["Across"].as_array().unwrap().len() + ipuz["clues"]["Down"].as_array().unwrap().len();
+        assert_eq!(clue_count, grid.count_placed_words());
+        assert_eq!(ipuz["clues"]["Across"][0][0], 1);
+
+        let svg = grid.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), nrows * ncols);
+        assert_eq!(svg.matches("fill=\"white\"").count(), grid.count_filled_cells());
+        assert_eq!(svg.matches("<text").count(), grid.clue_numbering().last().unwrap().0);
+    }
+
+    #[test]
+    fn test_clue_numbering() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let numbering = grid.clue_numbering();
+        assert_eq!(numbering.len(), grid.count_placed_words());
+
+        // BEARER and BOO both start in the first cell
+        assert_eq!(numbering[0].0, 1);
+        assert_eq!(numbering[0].2, Direction::Across);
+        assert_eq!(numbering[1].0, 1);
+        assert_eq!(numbering[1].2, Direction::Down);
+        assert_eq!(grid.get_word(numbering[0].1).unwrap().word_text, "BEARER");
+        assert_eq!(grid.get_word(numbering[1].1).unwrap().word_text, "BOO");
+    }
+
+    #[test]
+    fn test_shared_numbers() {
+        crate::logging::init_logger(true);
+        // Only the first cell, starting BEARER and BOO, heads both an across and a down word
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.shared_numbers(), vec![1]);
+
+        let grid = CrosswordGridBuilder::new().from_string("AB\nCD\n");
+        assert_eq!(grid.shared_numbers(), vec![1]);
+
+        let grid = CrosswordGrid::new_single_word("ALPHA");
+        assert_eq!(grid.shared_numbers(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_words_in_reading_order() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let order = grid.words_in_reading_order();
+        assert_eq!(order.len(), grid.count_placed_words());
+
+        // BEARER is the top-left-most word, and BOO starts in the same cell
+        assert_eq!(order[0].1, Direction::Across);
+        assert_eq!(grid.get_word(order[0].0).unwrap().word_text, "BEARER");
+        assert_eq!(grid.get_word(order[1].0).unwrap().word_text, "BOO");
+        assert_eq!(grid.get_word(order.last().unwrap().0).unwrap().word_text, "INVALUABLE");
+
+        let numbered: Vec<(usize, Direction)> = grid.clue_numbering().iter()
+            .map(|(_number, word_id, direction)| (*word_id, *direction))
+            .collect();
+        assert_eq!(order, numbered);
+    }
+
+    #[test]
+    fn test_normalized_placement() -> Result<(), CrosswordError> {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        assert_eq!(grid.normalized_placement(0), Some((0, 0, Direction::Across)));
+
+        // SPA ends at the first letter of ALPHA, so extends the grid upwards
+        let spa_word_id = grid.add_unplaced_word("SPA", "", None);
+        let unplaced_word_id = grid.add_unplaced_word("UNPLACED", "", None);
+        grid.place_word_in_cell(Location(0, 0), spa_word_id, 2, Direction::Down)?;
+        grid.fit_to_size();
+        assert_eq!(grid.normalized_placement(spa_word_id), Some((0, 0, Direction::Down)));
+        assert_eq!(grid.normalized_placement(0), Some((2, 0, Direction::Across)));
+        assert_eq!(grid.normalized_placement(unplaced_word_id), None);
+        assert_eq!(grid.normalized_placement(100), None);
+
+        // The buffer around the grid doesn't change the placements
+        grid.fit_to_size_with_buffer(2);
+        assert_eq!(grid.normalized_placement(spa_word_id), Some((0, 0, Direction::Down)));
+        assert_eq!(grid.normalized_placement(0), Some((2, 0, Direction::Across)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_clues_to_csv() -> Result<(), CrosswordError> {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let arrival_word_id = grid.add_unplaced_word("ARRIVAL", "Coming, \"finally\" (7)", None);
+        grid.place_word_in_cell(Location(0, 0), arrival_word_id, 0, Direction::Down)?;
+        grid.add_unplaced_word("UNPLACED", "Not in the grid (8)", None);
+
+        let csv = grid.clues_to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("number,direction,answer,clue,length"));
+        let rows: Vec<Vec<String>> = lines.map(parse_csv_line).collect();
+        assert_eq!(rows.len(), grid.count_placed_words());
+        assert_eq!(rows[0], vec!["1", "Across", "ALPHA", "Bla bla bla (6)", "5"]);
+        assert_eq!(rows[1], vec!["1", "Down", "ARRIVAL", "Coming, \"finally\" (7)", "7"]);
+
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let rows: Vec<Vec<String>> = grid.clues_to_csv().lines().skip(1).map(parse_csv_line).collect();
+        assert_eq!(rows.len(), grid.count_placed_words());
+        for row in rows.iter() {
+            assert_eq!(row.len(), 5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_answer_key() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let key = grid.answer_key();
+        assert_eq!(key.len(), grid.count_placed_words());
+        for (_clue_number, _direction, answer, _clue) in key.iter() {
+            assert!(!answer.is_empty());
+        }
+        assert_eq!(key[0], (1, Direction::Across, "BEARER".to_string(), grid.get_word(grid.clue_numbering()[0].1).unwrap().clue.clone()));
+        assert_eq!(key[1].2, "BOO");
+
+        let numbers: Vec<(usize, Direction)> = key.iter().map(|(number, direction, _answer, _clue)| (*number, *direction)).collect();
+        let expected: Vec<(usize, Direction)> = grid.clue_numbering().iter().map(|(number, _word_id, direction)| (*number, *direction)).collect();
+        assert_eq!(numbers, expected);
+    }
+}
@@ -0,0 +1,271 @@
+use log::debug;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap,HashSet};
+
+use super::CrosswordGrid;
+use super::Direction;
+use super::Location;
+use super::Word;
+
+/// Character marking a cell of a grid shape whose letter is still to be chosen,
+/// e.g. when building a shape with CrosswordGridBuilder to pass to fill_from_dictionary
+pub const UNFILLED_CHAR: char = '.';
+
+/// A placed word which still has cells to be filled, with the letters it has so far
+#[derive(Clone,Debug,PartialEq)]
+pub struct Slot {
+    pub word_id: usize,
+    pub start_location: Location,
+    pub direction: Direction,
+    pub pattern: Vec<Option<char>>,
+}
+
+/// Whether the uppercase answer fits the pattern, comparing letters as crate::words_matching_pattern does
+fn matches_pattern(word_text: &str, pattern: &[Option<char>]) -> bool {
+    word_text.chars().count() == pattern.len()
+        && word_text.chars().zip(pattern.iter()).all(|(c, p)| p.is_none_or(|p| p.to_ascii_uppercase() == c))
+}
+
+impl CrosswordGrid {
+    fn word_locations(&self, word_id: usize) -> Vec<Location> {
+        let mut locations: Vec<Location> = vec![];
+        if let Some((start, end, direction)) = self.get_word(word_id).ok().and_then(|w| w.get_location()) {
+            let mut location = start;
+            while location != end.relative_location_directed(1, direction) {
+                locations.push(location);
+                location = location.relative_location_directed(1, direction);
+            }
+        }
+        locations
+    }
+
+    /// Letters currently in the cells of the word, with None for cells still to be filled
+    /// (marked with UNFILLED_CHAR). Returns None if the word doesn't exist or isn't placed.
+    pub fn word_pattern(&self, word_id: usize) -> Option<Vec<Option<char>>> {
+        let word = self.get_word(word_id).ok()?;
+        if !word.is_placed() {
+            return None;
+        }
+        Some(self.word_locations(word_id).iter()
+             .map(|location| self.cell_map.get(location).map(|cell| cell.to_char()))
+             .map(|letter| letter.filter(|c| *c != UNFILLED_CHAR))
+             .collect())
+    }
+
+    /// Ids of placed words which still have at least one cell to be filled, in id order
+    pub fn open_slots(&self) -> Vec<usize> {
+        let mut slots: Vec<usize> = self.word_map.keys()
+            .filter(|id| self.word_pattern(**id).is_some_and(|pattern| pattern.contains(&None)))
+            .cloned()
+            .collect();
+        slots.sort();
+        slots
+    }
+
+    /// Every open slot with the number of answers from the dictionary which would fit it,
+    /// fewest first, so the slots hardest to fill come first. Ties are ordered by word id.
+    pub fn slot_options_count(&self, dict: &[&str]) -> Vec<(Slot, usize)> {
+        let mut counts: Vec<(Slot, usize)> = self.open_slots().into_iter()
+            .map(|word_id| {
+                let (start_location, _end_location, direction) = self.get_word(word_id).unwrap().get_location().unwrap();
+                let pattern = self.word_pattern(word_id).unwrap();
+                let options = crate::words_matching_pattern(dict, &pattern).len();
+                (Slot { word_id, start_location, direction, pattern }, options)
+            })
+            .collect();
+        counts.sort_by_key(|(slot, options)| (*options, slot.word_id));
+        counts
+    }
+
+    fn write_letters(&mut self, locations: &[Location], letters: &[char]) {
+        for (location, letter) in locations.iter().zip(letters.iter()) {
+            if let Some(cell) = self.cell_map.get_mut(location) {
+                cell.set_letter(*letter);
+            }
+        }
+    }
+
+    fn complete_text(&self, word_id: usize) -> Option<String> {
+        self.word_pattern(word_id)?.into_iter().collect()
+    }
+
+    /// Fill the grid by trying candidates in each open slot, always choosing the open slot
+    /// with the fewest candidates remaining. Any crossing word completed along the way must
+    /// also be an unused candidate. Returns the candidate chosen for each slot filled, or None
+    /// if there is no way to fill the grid, in which case the letters are left unchanged.
+    fn fill_open_slots(&mut self,
+                       candidates: &[Word],
+                       candidate_ids: &HashMap<String, usize>,
+                       used: &mut HashSet<usize>) -> Option<HashMap<usize, usize>> {
+        let mut best: Option<(usize, Vec<usize>)> = None;
+        for slot in self.open_slots() {
+            let pattern = self.word_pattern(slot).unwrap();
+            let matching: Vec<usize> = candidates.iter().enumerate()
+                .filter(|(index, candidate)| !used.contains(index) && matches_pattern(&candidate.word_text, &pattern))
+                .map(|(index, _candidate)| index)
+                .collect();
+            if best.as_ref().is_none_or(|(_slot, fewest)| matching.len() < fewest.len()) {
+                best = Some((slot, matching));
+            }
+        }
+
+        let (slot, matching) = match best {
+            Some(best) => best,
+            None => return Some(HashMap::new()),
+        };
+        debug!("Filling slot {} with {} candidates", slot, matching.len());
+
+        let locations = self.word_locations(slot);
+        let previous: Vec<char> = locations.iter().map(|l| self.cell_map[l].to_char()).collect();
+        let crossing_direction = self.get_word(slot).unwrap().get_location().unwrap().2.rotate();
+        let open_slots = self.open_slots();
+        let open_crossing_ids: Vec<usize> = locations.iter()
+            .filter_map(|l| self.cell_map[l].get_word_id(crossing_direction))
+            .filter(|id| open_slots.contains(id))
+            .collect();
+
+        for index in matching {
+            let letters: Vec<char> = candidates[index].word_text.chars().collect();
+            self.write_letters(&locations, &letters);
+            let mut chosen: HashMap<usize, usize> = HashMap::new();
+            chosen.insert(slot, index);
+            used.insert(index);
+
+            // Crossing words which this completes must be candidates too
+            let mut crossings_valid = true;
+            for crossing_id in open_crossing_ids.iter() {
+                if self.word_pattern(*crossing_id).is_some_and(|pattern| !pattern.contains(&None)) {
+                    let crossing_text = self.complete_text(*crossing_id).unwrap();
+                    match candidate_ids.get(&crossing_text) {
+                        Some(crossing_index) if !used.contains(crossing_index) => {
+                            used.insert(*crossing_index);
+                            chosen.insert(*crossing_id, *crossing_index);
+                        },
+                        _ => {
+                            crossings_valid = false;
+                            break;
+                        },
+                    }
+                }
+            }
+
+            if crossings_valid {
+                if let Some(rest) = self.fill_open_slots(candidates, candidate_ids, used) {
+                    chosen.extend(rest);
+                    return Some(chosen);
+                }
+            }
+
+            for chosen_index in chosen.values() {
+                used.remove(chosen_index);
+            }
+            self.write_letters(&locations, &previous);
+        }
+        None
+    }
+
+    /// Fill every cell marked with UNFILLED_CHAR using words from the dictionary, which
+    /// is given as lines in the clue file format. Each dictionary word is used at most once,
+    /// and ties between candidates are broken using the seed. Filled words take their answer
+    /// and clue from the dictionary. Returns false, leaving the grid unchanged, if the grid
+    /// can't be filled from the dictionary.
+    pub fn fill_from_dictionary(&mut self, dict: &[&str], seed: u64) -> bool {
+        let mut candidates: Vec<Word> = dict.iter()
+            .filter_map(|line| Word::new_parsed(line).ok())
+            .filter(|word| !word.word_text.is_empty())
+            .collect();
+        let mut seen: HashSet<String> = HashSet::new();
+        candidates.retain(|word| seen.insert(word.word_text.clone()));
+        let mut rng = StdRng::seed_from_u64(seed);
+        candidates.shuffle(&mut rng);
+
+        let candidate_ids: HashMap<String, usize> = candidates.iter().enumerate()
+            .map(|(index, word)| (word.word_text.clone(), index))
+            .collect();
+
+        // Words which are already complete can't be used again elsewhere
+        let mut used: HashSet<usize> = self.word_map.keys()
+            .filter_map(|id| self.complete_text(*id))
+            .filter_map(|text| candidate_ids.get(&text).cloned())
+            .collect();
+
+        match self.fill_open_slots(&candidates, &candidate_ids, &mut used) {
+            Some(chosen) => {
+                for (word_id, index) in chosen {
+                    let word = self.word_map.get_mut(&word_id).unwrap();
+                    word.word_text = candidates[index].word_text.clone();
+                    word.display_text = candidates[index].display_text.clone();
+                    word.clue = candidates[index].clue.clone();
+                }
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::CrosswordGridBuilder;
+
+    #[test]
+    fn test_fill_from_dictionary() {
+        crate::logging::init_logger(true);
+        let shape = ".....\n.   .\n.   .\n.   .\n.....\n";
+        let dict = ["MANGO::Tropical fruit", "APPLE::Orchard fruit", "TEETH::Biters", "ALIBI",
+                    "EARTH::Planet", "ELBOW", "ALOFT::Up high", "TULIP", "TOPAZ", "ALPHA"];
+
+        let mut grid = CrosswordGridBuilder::new().from_string(shape);
+        assert_eq!(grid.open_slots().len(), 4);
+        assert_eq!(grid.word_pattern(0), Some(vec![None; 5]));
+        assert!(grid.fill_from_dictionary(&dict, 13));
+        assert!(grid.open_slots().is_empty());
+        assert!(grid.is_valid_crossword().is_ok());
+        let mut answers = grid.all_answers();
+        answers.sort();
+        assert_eq!(answers, vec!["ALOFT", "APPLE", "EARTH", "TEETH"]);
+        assert!(grid.word_map.values().any(|w| w.clue == "Planet (5)"));
+
+        // Fixed letters are kept, and a grid which can't be filled is left unchanged
+        let mut grid = CrosswordGridBuilder::new().from_string("T....\n.   .\n.   .\n.   .\n.....\n");
+        let before = grid.to_string();
+        assert!(!grid.fill_from_dictionary(&dict, 13));
+        assert_eq!(grid.to_string(), before);
+        assert_eq!(grid.word_pattern(0).unwrap()[0], Some('T'));
+    }
+
+    #[test]
+    fn test_slot_options_count() {
+        crate::logging::init_logger(true);
+        let dict = ["MANGO", "APPLE", "TEETH", "ALIBI", "EARTH", "ELBOW", "ALOFT", "TOPAZ"];
+        assert!(matches_pattern("APPLE", &[Some('a'), None, Some('p'), None, Some('E')]));
+        assert!(!matches_pattern("APPLE", &[Some('a'), None, None]));
+
+        // Only APPLE fits the top row, but any word fits the bottom row
+        let grid = CrosswordGridBuilder::new().from_string("APPL.\n.   .\n.   .\n.   .\n.....\n");
+        let counts = grid.slot_options_count(&dict);
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts[0].0.pattern, vec![Some('A'), Some('P'), Some('P'), Some('L'), None]);
+        assert_eq!(counts[0].0.start_location, Location(0, 0));
+        assert_eq!(counts[0].0.direction, Direction::Across);
+        assert_eq!(counts[0].1, 1);
+        let (bottom_row, options) = counts.iter().find(|(slot, _)| slot.start_location == Location(4, 0)).unwrap();
+        assert_eq!(bottom_row.direction, Direction::Across);
+        assert_eq!(*options, 8);
+        assert!(counts.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+        // Lowercase letters in the grid match the same answers as uppercase ones
+        let lowercase = CrosswordGridBuilder::new().from_string("appl.\n.   .\n.   .\n.   .\n.....\n");
+        let lowercase_counts = lowercase.slot_options_count(&dict);
+        assert_eq!(lowercase_counts[0].0.pattern, vec![Some('a'), Some('p'), Some('p'), Some('l'), None]);
+        let options: Vec<usize> = lowercase_counts.iter().map(|(_slot, options)| *options).collect();
+        assert_eq!(options, counts.iter().map(|(_slot, options)| *options).collect::<Vec<usize>>());
+
+        let mut grid = grid;
+        assert!(grid.fill_from_dictionary(&dict, 13));
+        assert!(grid.slot_options_count(&dict).is_empty());
+    }
+}
@@ -225,6 +225,33 @@ impl CrosswordGrid {
         }
     }
 
+    /// A mask of the box containing the filled cells of the grid, with 1 for cells
+    /// containing a letter and 0 otherwise.
+    pub fn fill_mask(&self) -> Array2<u8> {
+        let top_left = self.filled_bounding_box().map_or(Location(0, 0), |(top_left, _bottom_right)| top_left);
+        Array2::from_shape_fn(self.scoring_dimensions(), |(row, col)| {
+            let location = Location(top_left.0 + row as isize,
+                                    top_left.1 + col as isize);
+            self.cell_map.get(&location).map_or(0, |cell| cell.contains_letter() as u8)
+        })
+    }
+
+    /// Check whether any 2x2 block of filled cells touches the given placed word, looking
+    /// only at the region of the grid immediately around the word.
+    pub(super) fn solid_square_around_word(&self, word_id: usize) -> bool {
+        if let Some((start, end, _direction)) = self.get_word(word_id).ok().and_then(|w| w.get_location()) {
+            let nrows = (end.0 - start.0 + 3) as usize;
+            let ncols = (end.1 - start.1 + 3) as usize;
+            let region: Array2<u8> = Array2::from_shape_fn((nrows, ncols), |(row, col)| {
+                let location = Location(start.0 - 1 + row as isize, start.1 - 1 + col as isize);
+                self.cell_map.get(&location).map_or(0, |cell| cell.contains_letter() as u8)
+            });
+            look_for_squares(&region)
+        } else {
+            false
+        }
+    }
+
     fn to_matrix(&self) -> CrosswordGridMatrix {
         let mut row: isize = self.top_left_cell_index.0;
         let mut col: isize = self.top_left_cell_index.1;
@@ -258,16 +285,16 @@ mod tests {
     #[test]
     fn test_to_matrix() {
         crate::logging::init_logger(true);
-        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
         println!("{:#?}", grid.to_matrix());
 
-        let grid = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt");
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt").unwrap();
         println!("{:#?}", grid.to_matrix());
 
         let grid = CrosswordGrid::new_single_word("ALPHA");
         println!("{:#?}", grid.to_matrix());
 
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt").unwrap();
         grid.add_unplaced_word("PROBONO", "", None);
         grid.add_unplaced_word("PASTURE", "", None);
         grid.add_unplaced_word("VETO", "", None);
@@ -321,9 +348,9 @@ mod tests {
     fn test_matrix_best_probably_compatible() {
         crate::logging::init_logger(true);
 
-        let grid1 = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt");
-        let grid2 = CrosswordGridBuilder::new().from_file("tests/resources/everyman_compatible.txt");
-        let grid3 = CrosswordGridBuilder::new().from_file("tests/resources/built_up.txt");
+        let grid1 = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt").unwrap();
+        let grid2 = CrosswordGridBuilder::new().from_file("tests/resources/everyman_compatible.txt").unwrap();
+        let grid3 = CrosswordGridBuilder::new().from_file("tests/resources/built_up.txt").unwrap();
         println!("{:#?}", grid1.to_matrix());
         println!("{:#?}", grid2.to_matrix());
         println!("{:#?}", grid3.to_matrix());
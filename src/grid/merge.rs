@@ -16,6 +16,8 @@ impl CrosswordGrid {
         compatible
     }
 
+    /// Merge the other grid into this one in the configuration with the most overlaps, if it
+    /// has at least min_overlaps. Returns false, leaving this grid unchanged, if no merge is made.
     pub fn try_merge_with_grid(&mut self, other: &CrosswordGrid, min_overlaps: usize) -> bool {
         // First check if the word lists are compatible i.e. that they don't share any placed words
         let mut success = self.words_placed_compatible(other);
@@ -38,7 +40,18 @@ impl CrosswordGrid {
         success
     }
 
+    /// Place the words of the other grid into this one, shifted by the given rows and columns.
+    /// Returns an error, leaving this grid unchanged, if the merged words form any
+    /// spurious words or are otherwise placed invalidly.
     pub fn merge_with_grid(&mut self, other: &CrosswordGrid, row_shift: isize, col_shift: isize) -> Result<(), CrosswordError> {
+        // Merge into a copy, so that a failed merge doesn't leave this grid partially merged
+        let mut merged = self.clone();
+        merged.merge_with_grid_in_place(other, row_shift, col_shift)?;
+        *self = merged;
+        Ok(())
+    }
+
+    fn merge_with_grid_in_place(&mut self, other: &CrosswordGrid, row_shift: isize, col_shift: isize) -> Result<(), CrosswordError> {
         assert!(other.black_cells_valid());
         self.grow_to_fit_merge(other, row_shift, col_shift);
         self.fill_black_cells();
@@ -54,6 +67,7 @@ impl CrosswordGrid {
                 assert!(result.is_ok(), "Failed to place word {} in location {:?}. Other word: {:?}", word_id, shifted_location, other_word);
             }
         }
+        self.check_no_accidental_words()?;
         self.check_valid();
         self.check_all_word_placement_valid()
     }
@@ -86,7 +100,7 @@ mod tests {
     use super::super::Direction;
 
     fn setup_merge() -> (CrosswordGrid, CrosswordGrid) {
-        let mut grid1 = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt");
+        let mut grid1 = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt").unwrap();
         let mut grid2 = CrosswordGrid::new_single_word("SIXTY");
         grid2.update_word_id(0, 100);
         let rusty_id = grid2.add_unplaced_word("RUSTY", "", None);
@@ -117,6 +131,27 @@ mod tests {
         println!("{}", grid1.to_string());
     }
 
+    #[test]
+    fn test_merge_rejects_accidental_words() {
+        crate::logging::init_logger(true);
+        let mut grid1 = CrosswordGrid::new_single_word("BEER");
+        grid1.add_unplaced_word_at_id("BEAR", "", 1, None);
+        let mut grid2 = CrosswordGrid::new_single_word("BEAR");
+        grid2.update_word_id(0, 1);
+        grid2.add_unplaced_word_at_id("BEER", "", 0, None);
+
+        // BEAR starting above the R of BEER gives the spurious down word BR
+        //    BEAR
+        // BEER
+        let before = grid1.to_string();
+        assert_eq!(grid1.merge_with_grid(&grid2, -1, 3), Err(CrosswordError::AccidentalWord(Location(-1, 3), Direction::Down)));
+        assert_eq!(grid1.to_string(), before);
+        assert!(!grid1.get_word(1).unwrap().is_placed());
+
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.check_no_accidental_words(), Ok(()));
+    }
+
     #[test]
     #[should_panic]
     fn test_disconnected() {
@@ -0,0 +1,77 @@
+use log::warn;
+use serde::{Deserialize,Serialize};
+use std::collections::BTreeMap;
+
+/// Details about the puzzle as a whole, such as its title and author, read from
+/// `# key: value` lines at the top of a clue file
+#[derive(Clone,Debug,Default,PartialEq,Deserialize,Serialize)]
+pub struct PuzzleMeta {
+    entries: BTreeMap<String, String>,
+}
+
+impl PuzzleMeta {
+    /// Split the header from the start of a clue file, returning the metadata and the
+    /// remaining lines. The header is every line starting with '#' before the first blank
+    /// line or answer line. Keys are converted to lowercase.
+    pub fn parse_header(contents: &str) -> (Self, Vec<&str>) {
+        let mut meta = PuzzleMeta::default();
+        let mut lines = contents.split('\n').peekable();
+        while let Some(line) = lines.next_if(|line| line.starts_with('#')) {
+            match line[1..].split_once(':') {
+                Some((key, value)) => meta.insert(key, value),
+                None => warn!("Ignoring header line without a key: {}", line),
+            }
+        }
+        (meta, lines.collect())
+    }
+
+    pub fn insert(&mut self, key: &str, value: &str) {
+        self.entries.insert(key.trim().to_lowercase(), value.trim().to_string());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(&key.to_lowercase()).map(|value| value.as_str())
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.get("title")
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.get("author")
+    }
+
+    pub fn date(&self) -> Option<&str> {
+        self.get("date")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All the entries, ordered by key
+    pub fn entries(&self) -> &BTreeMap<String, String> {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        crate::logging::init_logger(true);
+        let contents = "# Title: Spring puzzle\n# author:  A. Setter \n#no key here\nAPPLE::Fruit (5)::\n# DATE: later\nPEAR";
+        let (meta, lines) = PuzzleMeta::parse_header(contents);
+        assert_eq!(meta.title(), Some("Spring puzzle"));
+        assert_eq!(meta.author(), Some("A. Setter"));
+        assert_eq!(meta.date(), None);
+        assert_eq!(meta.entries().len(), 2);
+        assert_eq!(lines, vec!["APPLE::Fruit (5)::", "# DATE: later", "PEAR"]);
+
+        let (meta, lines) = PuzzleMeta::parse_header("APPLE\nPEAR\n");
+        assert!(meta.is_empty());
+        assert_eq!(lines, vec!["APPLE", "PEAR", ""]);
+    }
+}
@@ -3,6 +3,7 @@ use log::debug;
 use std::collections::HashMap;
 use std::fmt;
 use ndarray::Array2;
+use serde::{Deserialize,Deserializer,Serialize,Serializer};
 use thiserror::Error;
 
 mod builder;
@@ -17,13 +18,25 @@ mod pdf_conversion;
 mod matrix;
 mod merge;
 mod validity;
-
-use word::Word;
+mod export;
+mod meta;
+mod reflect;
+mod fill;
+
+pub(crate) use word::Word;
+pub(crate) use word::parse_answer_text;
+pub(crate) use word::{parse_answer_string,clue_word_lengths,ParseError};
+pub use word::EnumerationStyle;
 use cell::Cell;
+pub use cell::PublicCell;
 pub use builder::CrosswordGridBuilder;
 pub use pdf_conversion::CrosswordPrinter;
+pub use meta::PuzzleMeta;
+pub use fill::{UNFILLED_CHAR,Slot};
 
 static VALID_ANSWERCHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// Letters which are hard to cross, so words using them are hard to place
+pub(crate) static RARE_LETTERS: &str = "JKQVXZ";
 static VALID_CLUECHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_— -;:,.?!@'“”`‘’\"&*()$£%";
 
 #[derive(Error,Debug,PartialEq)]
@@ -63,9 +76,45 @@ pub enum CrosswordError {
 
     #[error("Word not found in grid object {0}")]
     WordNotFound(usize),
+
+    #[error("Row {0} of character grid has length {1}, expected {2}")]
+    InvalidCharGridRow(usize, usize, usize),
+
+    #[error("Invalid character '{0}' found in character grid")]
+    InvalidCharGridChar(char),
+
+    #[error("Character grid contains no words")]
+    NoWordsInCharGrid,
+
+    #[error("Grid is not connected")]
+    DisconnectedGrid,
+
+    #[error("Word {0} is contained within word {1} along the same line")]
+    ContainedWord(usize, usize),
+
+    #[error("Invalid compact grid code: {0}")]
+    InvalidCompactCode(String),
+
+    #[error("Cells of word {0} don't form a single straight line matching its length")]
+    SelfCrossingWord(usize),
+
+    #[error("Word {0} has length {1}, shorter than the minimum allowed")]
+    WordTooShort(usize, usize),
+
+    #[error("Run of letters starting at {0:?} in direction {1:?} doesn't match a single placed word")]
+    AccidentalWord(Location, Direction),
+
+    #[error("Invalid workspace JSON: {0}")]
+    InvalidWorkspace(String),
+
+    #[error("Black cells don't match the cells just before and after each placed word")]
+    InvalidBlackCells,
+
+    #[error("Letter at {1:?} doesn't match the letter of word {0} which should be there")]
+    WordLetterMismatch(usize, Location),
 }
 
-#[derive(Clone,Copy,Debug,PartialEq,Eq,Ord,PartialOrd,Hash)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Ord,PartialOrd,Hash,Deserialize,Serialize)]
 pub enum Direction {
     Across,
     Down,
@@ -80,7 +129,14 @@ impl Direction {
     }
 }
 
-#[derive(Clone,Copy,Eq,Hash)]
+/// Whether a filled cell is checked i.e. belongs to both an across and a down word
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum CheckStatus {
+    Checked,
+    Unchecked,
+}
+
+#[derive(Clone,Copy,Eq,Hash,Deserialize,Serialize)]
 pub struct Location(pub isize, pub isize);
 
 impl fmt::Debug for Location {
@@ -108,12 +164,33 @@ impl Location {
     }
 }
 
-#[derive(Clone)]
+// JSON only allows string keys, so store the cell map as a list of (location, cell) pairs
+fn serialize_cell_map<S>(cell_map: &HashMap<Location, Cell>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut cells: Vec<(&Location, &Cell)> = cell_map.iter().collect();
+    cells.sort_by_key(|(location, _cell)| (location.0, location.1));
+    serializer.collect_seq(cells)
+}
+
+fn deserialize_cell_map<'de, D>(deserializer: D) -> Result<HashMap<Location, Cell>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let cells: Vec<(Location, Cell)> = Vec::deserialize(deserializer)?;
+    Ok(cells.into_iter().collect())
+}
+
+#[derive(Clone,Deserialize,Serialize)]
 pub struct CrosswordGrid {
+    #[serde(serialize_with = "serialize_cell_map", deserialize_with = "deserialize_cell_map")]
     cell_map: HashMap<Location, Cell>,
     word_map: HashMap<usize, Word>,
     top_left_cell_index: Location,
     bottom_right_cell_index: Location,
+    #[serde(default)]
+    meta: PuzzleMeta,
 }
 
 impl fmt::Debug for CrosswordGrid {
@@ -158,6 +235,44 @@ impl CrosswordGrid {
         builder.from_string(word)
     }
 
+    /// Build a grid from a matrix of letters, treating the blank character as an empty cell.
+    /// All rows must be the same length, and the letters must form a single connected grid.
+    pub fn from_char_grid(grid: &[Vec<char>], blank: char) -> Result<Self, CrosswordError> {
+        let ncols = grid.first().map_or(0, |row| row.len());
+        let mut string = String::new();
+        for (row_index, row) in grid.iter().enumerate() {
+            if row.len() != ncols {
+                return Err(CrosswordError::InvalidCharGridRow(row_index, row.len(), ncols));
+            }
+            for c in row.iter() {
+                if *c == blank {
+                    string.push(' ');
+                } else if VALID_ANSWERCHARS.contains(c.to_ascii_uppercase()) {
+                    string.push(c.to_ascii_uppercase());
+                } else {
+                    return Err(CrosswordError::InvalidCharGridChar(*c));
+                }
+            }
+            string.push('\n');
+        }
+
+        // Every word is at least two letters long, so there must be two adjacent letters
+        let has_letter = |row: usize, col: usize| grid.get(row).and_then(|r| r.get(col)).is_some_and(|c| *c != blank);
+        let contains_word = (0..grid.len()).any(|row| {
+            (0..ncols).any(|col| has_letter(row, col) && (has_letter(row + 1, col) || has_letter(row, col + 1)))
+        });
+        if !contains_word {
+            return Err(CrosswordError::NoWordsInCharGrid);
+        }
+
+        let grid = builder::CrosswordGridBuilder::new().build_unchecked(&string);
+        if grid.to_graph().is_connected() {
+            Ok(grid)
+        } else {
+            Err(CrosswordError::DisconnectedGrid)
+        }
+    }
+
     fn new_from_wordmap_single_placed(word_id: usize,
                                       direction: Direction,
                                       mut word_map: HashMap<usize, Word>) -> Self {
@@ -188,13 +303,14 @@ impl CrosswordGrid {
             word_map,
             top_left_cell_index: Location(0, 0),
             bottom_right_cell_index: location.relative_location_directed(-1, direction),
+            meta: PuzzleMeta::default(),
         };
 
         grid.fit_to_size();
         grid
     }
 
-    fn get_all_intersections(&self) -> Vec<(usize, usize)> {
+    pub(crate) fn get_all_intersections(&self) -> Vec<(usize, usize)> {
         let mut edges: Vec<(usize, usize)> = vec![];
         for cell in self.cell_map.values().filter(|c| c.is_intersection()) {
             edges.push((cell.get_across_word_id().unwrap(),
@@ -241,6 +357,11 @@ impl CrosswordGrid {
         string
     }
 
+    /// Returns the letters of the grid as a matrix, with ' ' for cells without a letter
+    pub fn to_char_grid(&self) -> Vec<Vec<char>> {
+        self.to_string().lines().map(|line| line.chars().collect()).collect()
+    }
+
     pub fn to_string(&self) -> String {
         let mut string: String = String::from("");
         let mut row = self.top_left_cell_index.0 + 1;
@@ -258,6 +379,43 @@ impl CrosswordGrid {
         string
     }
 
+    /// As to_string, but without any leading or trailing lines made up only of spaces,
+    /// and without the final newline. Columns are left as they are.
+    pub fn to_string_tight(&self) -> String {
+        let string = self.to_string();
+        let lines: Vec<&str> = string.lines().collect();
+        let is_blank = |line: &&str| line.chars().all(|c| c == ' ');
+        match (lines.iter().position(|line| !is_blank(line)), lines.iter().rposition(|line| !is_blank(line))) {
+            (Some(first), Some(last)) => lines[first..=last].join("\n"),
+            _ => String::new(),
+        }
+    }
+
+    /// The solution as rows of letters, cropped to the smallest box containing every letter,
+    /// with spaces for cells without a letter. Clues and the position in the grid are ignored.
+    pub fn canonical_string(&self) -> String {
+        let rows: Vec<Vec<char>> = self.to_char_grid();
+        let has_letter = |row: &Vec<char>| row.iter().any(|c| *c != ' ');
+        let (first_row, last_row) = match (rows.iter().position(has_letter), rows.iter().rposition(has_letter)) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return String::new(),
+        };
+        let first_col = rows.iter().filter_map(|row| row.iter().position(|c| *c != ' ')).min().unwrap();
+        let last_col = rows.iter().filter_map(|row| row.iter().rposition(|c| *c != ' ')).max().unwrap();
+        rows[first_row..=last_row].iter()
+            .map(|row| row[first_col..=last_col].iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Stable id of the solution, the same on any machine for grids with the same letters in
+    /// the same shape whatever their clues. A grid and its transpose are the same puzzle, so
+    /// share an id. This is the FNV-1a hash of whichever canonical string comes first.
+    pub fn puzzle_id(&self) -> String {
+        let canonical = std::cmp::min(self.canonical_string(), self.transposed().canonical_string());
+        format!("{:016x}", crate::utils::fnv1a_64(&canonical))
+    }
+
     pub fn check_valid(&self) {
         assert!(self.top_left_cell_index.0 <= self.bottom_right_cell_index.0);
         assert!(self.top_left_cell_index.1 <= self.bottom_right_cell_index.1);
@@ -286,6 +444,10 @@ impl CrosswordGrid {
             }
         }
 
+        if let Err(error) = self.check_no_self_crossing() {
+            panic!("{}", error);
+        }
+
         let graph = self.to_graph();
         debug!("{:#?}", graph);
         debug!("{:#?}", self.word_map);
@@ -293,6 +455,15 @@ impl CrosswordGrid {
         assert!(graph.is_connected());
     }
 
+    /// Details about the puzzle such as its title, read from the header of the clue file
+    pub fn meta(&self) -> &PuzzleMeta {
+        &self.meta
+    }
+
+    pub fn set_meta(&mut self, meta: PuzzleMeta) {
+        self.meta = meta;
+    }
+
     fn find_lowest_unused_word_id(&self) -> usize {
         let mut word_id: usize = 0;
         while self.word_map.contains_key(&word_id) {
@@ -312,6 +483,14 @@ impl CrosswordGrid {
         word_id
     }
 
+    /// Add a word which has already been parsed, keeping its display text and clue
+    pub(crate) fn add_parsed_unplaced_word(&mut self, mut word: Word) -> usize {
+        let word_id = self.find_lowest_unused_word_id();
+        word.remove_placement();
+        self.word_map.insert(word_id, word);
+        word_id
+    }
+
     pub fn update_word_id(&mut self, old_word_id: usize, new_word_id: usize) {
         // Move in hashmap
         let word: Word = self.word_map.remove(&old_word_id).unwrap();
@@ -373,4 +552,76 @@ mod tests {
                                      [0, 0, 0, 0, 0, 0]]);
         Ok(())
     }
+
+    #[test]
+    fn test_to_string_tight() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        assert_eq!(grid.to_string(), "ALPHA\n");
+        assert_eq!(grid.to_string_tight(), "ALPHA");
+
+        // A wider buffer leaves blank rows and columns around the word
+        grid.fit_to_size_with_buffer(3);
+        assert_eq!(grid.to_string().lines().count(), 5);
+        let tight = grid.to_string_tight();
+        assert_eq!(tight, "  ALPHA  ");
+        assert!(!tight.ends_with('\n'));
+
+        let grid = builder::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.to_string_tight(), grid.to_string().trim_end_matches('\n'));
+    }
+
+    #[test]
+    fn test_puzzle_id() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.fit_to_size_with_buffer(3);
+        assert_eq!(grid.canonical_string(), "ALPHA");
+
+        let grid = builder::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let id = grid.puzzle_id();
+        assert_eq!(id.len(), 16);
+        assert_eq!(grid.transposed().puzzle_id(), id);
+        assert_ne!(grid.transposed().canonical_string(), grid.canonical_string());
+
+        // Clues don't affect the id, but the letters do
+        let mut reclued = grid.clone();
+        for word in reclued.word_map.values_mut() {
+            word.clue = "Something else (3)".to_string();
+        }
+        assert_eq!(reclued.puzzle_id(), id);
+        assert_ne!(CrosswordGrid::new_single_word("ALPHA").puzzle_id(), id);
+        // The down version of the word sorts first, as a newline comes before any letter
+        assert_eq!(CrosswordGrid::new_single_word("ALPHA").puzzle_id(),
+                   format!("{:016x}", crate::utils::fnv1a_64("A\nL\nP\nH\nA")));
+    }
+
+    #[test]
+    fn test_char_grid() -> Result<(), CrosswordError> {
+        crate::logging::init_logger(true);
+        let grid = builder::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let char_grid = grid.to_char_grid();
+        assert_eq!(char_grid.len(), 9);
+        assert_eq!(char_grid[0].len(), 10);
+
+        let rebuilt = CrosswordGrid::from_char_grid(&char_grid, ' ')?;
+        rebuilt.check_valid();
+        assert_eq!(rebuilt.to_string(), grid.to_string());
+        assert_eq!(rebuilt.count_placed_words(), grid.count_placed_words());
+
+        let hashed: Vec<Vec<char>> = char_grid.iter()
+            .map(|row| row.iter().map(|c| if *c == ' ' { '#' } else { c.to_ascii_lowercase() }).collect())
+            .collect();
+        assert_eq!(CrosswordGrid::from_char_grid(&hashed, '#')?.to_string(), grid.to_string());
+
+        assert_eq!(CrosswordGrid::from_char_grid(&[vec!['A', 'B'], vec!['C']], ' ').err(),
+                   Some(CrosswordError::InvalidCharGridRow(1, 1, 2)));
+        assert_eq!(CrosswordGrid::from_char_grid(&[vec!['A', '1']], ' ').err(),
+                   Some(CrosswordError::InvalidCharGridChar('1')));
+        assert_eq!(CrosswordGrid::from_char_grid(&[vec!['A', ' ', 'B']], ' ').err(),
+                   Some(CrosswordError::NoWordsInCharGrid));
+        assert_eq!(CrosswordGrid::from_char_grid(&[vec!['A', 'B', ' ', 'C', 'D']], ' ').err(),
+                   Some(CrosswordError::DisconnectedGrid));
+        Ok(())
+    }
 }
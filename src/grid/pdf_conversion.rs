@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::fs;
+use std::path::Path;
 use std::process::Command;
 
 use handlebars::{Handlebars, RenderContext, Helper, Context, JsonRender, HelperResult, Output};
@@ -80,6 +81,15 @@ impl CrosswordPrinter {
         CrosswordPrinter::new_with_settings(grid, "   {}", "", true)
     }
 
+    // Clear everything accumulated while printing, so the grid can be printed again
+    fn reset(&mut self) {
+        self.last_clue_number = 0;
+        self.visited_word_ids.clear();
+        self.across_clues.clear();
+        self.down_clues.clear();
+        self.printed_grid.clear();
+    }
+
     fn add_clue(&mut self, clue_number: usize, word_id: usize, across: bool) {
         let word = self.grid.word_map.get(&word_id).unwrap();
         let answer = if self.obscure_answers {
@@ -91,6 +101,7 @@ impl CrosswordPrinter {
             "number": clue_number,
             "answer": answer,
             "clue": word.clue,
+            "difficulty": word.difficulty,
         });
         if across {
             self.across_clues.push(clue_info);
@@ -152,7 +163,24 @@ impl CrosswordPrinter {
         self.printed_grid.push_str("|.\n");
     }
 
-    pub fn print(&mut self) -> String {
+    // Set up the LaTeX templates, with the preamble and the rendering of a single grid
+    // shared between the single and multi-grid documents
+    fn latex_renderer() -> Handlebars<'static> {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(handlebars::no_escape);
+        handlebars.register_helper("braced", Box::new(wrap_in_braces));
+
+        handlebars.register_partial("preamble", include_str!("../../templates/latex_preamble.hbs")).unwrap();
+        handlebars.register_partial("grid", include_str!("../../templates/latex_grid.hbs")).unwrap();
+        handlebars.register_template_string("template", include_str!("../../templates/latex_template.hbs")).unwrap();
+        handlebars.register_template_string("many_template", include_str!("../../templates/latex_many_template.hbs")).unwrap();
+        handlebars
+    }
+
+    // Walk the grid formatting each cell and collecting the clues, returning the data
+    // used to render the grid in the templates
+    fn grid_data(&mut self) -> Value {
+        self.reset();
         let mut row = self.grid.top_left_cell_index.0 + 1;
         let mut col = self.grid.top_left_cell_index.1 + 1;
         while row < self.grid.bottom_right_cell_index.0 {
@@ -167,22 +195,74 @@ impl CrosswordPrinter {
         }
 
         let (rows, cols) = self.grid.get_grid_dimensions();
-        let data = &json!({
+        json!({
             "num_cols": cols,
             "num_rows": rows,
             "puzzle_content": self.printed_grid,
             "across_clues": self.across_clues,
             "down_clues": self.down_clues
+        })
+    }
+
+    pub fn print(&mut self) -> String {
+        let data = self.grid_data();
+        CrosswordPrinter::latex_renderer().render("template", &data).unwrap()
+    }
+
+    /// Print several grids into a single LaTeX document, with one grid and its clues
+    /// on each page. Blank cells are printed black.
+    pub fn print_many(grids: Vec<CrosswordGrid>, show_solution: bool) -> String {
+        let grids_data: Vec<Value> = grids.into_iter()
+            .map(|grid| CrosswordPrinter::new(grid, true, show_solution).grid_data())
+            .collect();
+        let data = json!({
+            "grids": grids_data,
         });
+        CrosswordPrinter::latex_renderer().render("many_template", &data).unwrap()
+    }
 
-        let mut handlebars = Handlebars::new();
-        handlebars.register_escape_fn(handlebars::no_escape);
-        handlebars.register_helper("braced", Box::new(wrap_in_braces));
+    /// Print several grids into a single document as for print_many, and compile it
+    /// to a PDF. Files are written to output_root with .tex and .pdf extensions.
+    pub fn print_many_to_pdf(grids: Vec<CrosswordGrid>, show_solution: bool, output_root: &str) {
+        let output_path = Path::new(output_root);
+        let folder = output_path.parent().and_then(|p| p.to_str()).filter(|p| !p.is_empty()).unwrap_or(".");
+        fs::create_dir_all(folder).expect("Folder couldn't be created");
 
-        let template_string = include_str!("../../templates/latex_template.hbs");
-        handlebars.register_template_string("template", &template_string);
+        let tex_file = format!("{}.tex", output_root);
+        fs::write(&tex_file, CrosswordPrinter::print_many(grids, show_solution).as_bytes()).expect("Unable to write to file!");
+        CrosswordPrinter::compile_latex(folder, &tex_file);
+        println!("{}.pdf", output_root);
+    }
 
-        handlebars.render("template", &data).unwrap()
+    fn compile_latex(folder: &str, tex_file: &str) {
+        let output = Command::new("pdflatex")
+            .arg("-output-directory")
+            .arg(folder)
+            .arg(tex_file)
+            .output()
+            .expect("Command failed");
+        println!("status: {}", output.status);
+        println!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+    }
+
+    /// Print both the blank puzzle and the solution, returning (blank, solution) LaTeX.
+    /// Blank cells are formatted as for this printer, and the printer's own settings
+    /// are unchanged afterwards.
+    pub fn print_both(&mut self) -> (String, String) {
+        let filled_cell_format = self.filled_cell_format.clone();
+        let obscure_answers = self.obscure_answers;
+
+        self.filled_cell_format = String::new();
+        self.obscure_answers = true;
+        let blank = self.print();
+
+        self.filled_cell_format = "[Sf]".to_string();
+        self.obscure_answers = false;
+        let solution = self.print();
+
+        self.filled_cell_format = filled_cell_format;
+        self.obscure_answers = obscure_answers;
+        (blank, solution)
     }
 
     pub fn print_to_file(&mut self, filename: &str) {
@@ -194,14 +274,7 @@ impl CrosswordPrinter {
         let tex_file = format!("{}/{}.tex", folder, filename_root);
         let pdf_file = format!("{}/{}.pdf", folder, filename_root);
         self.print_to_file(&tex_file);
-        let output = Command::new("pdflatex")
-            .arg("-output-directory")
-            .arg(folder)
-            .arg(tex_file)
-            .output()
-            .expect("Command failed");
-        println!("status: {}", output.status);
-        println!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+        CrosswordPrinter::compile_latex(folder, &tex_file);
         println!("{}", pdf_file);
     }
 }
@@ -1,6 +1,15 @@
 use log::debug;
+use std::cmp;
+use std::collections::{BTreeMap,HashMap};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash,Hasher};
 
 use super::CrosswordGrid;
+use super::CheckStatus;
+use super::Location;
+use super::Direction;
+use super::PublicCell;
+use super::RARE_LETTERS;
 
 impl CrosswordGrid {
     pub fn count_all_words(&self) -> usize {
@@ -15,6 +24,25 @@ impl CrosswordGrid {
         self.word_map.values().filter(|w| !w.is_placed()).count()
     }
 
+    /// Answers of all the words, placed or not, ordered by word id
+    pub fn all_answers(&self) -> Vec<String> {
+        let mut answers: Vec<(usize, String)> = self.word_map.iter()
+            .map(|(id, w)| (*id, w.word_text.clone()))
+            .collect();
+        answers.sort();
+        answers.into_iter().map(|(_id, answer)| answer).collect()
+    }
+
+    /// Answers of the words which are not placed in the grid, ordered by word id
+    pub fn unplaced_answers(&self) -> Vec<String> {
+        let mut unplaced: Vec<(usize, String)> = self.word_map.iter()
+            .filter(|(_id, w)| !w.is_placed())
+            .map(|(id, w)| (*id, w.word_text.clone()))
+            .collect();
+        unplaced.sort();
+        unplaced.into_iter().map(|(_id, answer)| answer).collect()
+    }
+
     pub fn count_intersections(&self) -> usize {
         let mut intersections: usize = 0;
         for cell in self.cell_map.values() {
@@ -25,6 +53,202 @@ impl CrosswordGrid {
         intersections
     }
 
+    /// Every intersection cell, with the ids of the across and down words crossing there,
+    /// ordered by location
+    pub fn intersections(&self) -> Vec<(Location, usize, usize)> {
+        let mut intersections: Vec<(Location, usize, usize)> = self.cell_map.iter()
+            .filter_map(|(location, cell)| {
+                match (cell.get_across_word_id(), cell.get_down_word_id()) {
+                    (Some(across_word_id), Some(down_word_id)) => Some((*location, across_word_id, down_word_id)),
+                    _ => None,
+                }
+            })
+            .collect();
+        intersections.sort_by_key(|a| (a.0.0, a.0.1));
+        intersections
+    }
+
+    /// Each pair of crossing words, lowest word id first, with the number of cells they share,
+    /// ordered by word ids. In a valid grid every weight is 1, since an across word and a down
+    /// word can only cross once, so a weight of 2 or more means the cells are inconsistent.
+    pub fn weighted_crossing_edges(&self) -> Vec<((usize, usize), usize)> {
+        let mut weights: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for (_location, across_word_id, down_word_id) in self.intersections() {
+            let pair = (cmp::min(across_word_id, down_word_id), cmp::max(across_word_id, down_word_id));
+            *weights.entry(pair).or_insert(0) += 1;
+        }
+        weights.into_iter().collect()
+    }
+
+    /// Sizes of the connected components, in words, that would be left if the word were
+    /// unplaced, largest first. More than one component means the grid would split, so the
+    /// word is structurally critical. The grid itself is left unchanged.
+    /// Returns an empty list if the word isn't placed.
+    pub fn components_if_removed(&self, word_id: usize) -> Vec<usize> {
+        if !self.word_map.get(&word_id).is_some_and(|word| word.is_placed()) {
+            return vec![];
+        }
+        let mut sizes: Vec<usize> = self.to_graph()
+            .components_after_deleting_node(word_id)
+            .iter()
+            .map(|component| component.len())
+            .collect();
+        sizes.sort_by_key(|size| cmp::Reverse(*size));
+        sizes
+    }
+
+    /// Placed words which cross exactly one other word, as (word_id, length) ordered by word_id.
+    /// Long pendant words are poorly checked, so are good candidates for pruning.
+    pub fn pendant_words(&self) -> Vec<(usize, usize)> {
+        let mut crossings: HashMap<usize, usize> = HashMap::new();
+        for (across_word_id, down_word_id) in self.get_all_intersections() {
+            *crossings.entry(across_word_id).or_insert(0) += 1;
+            *crossings.entry(down_word_id).or_insert(0) += 1;
+        }
+        let mut pendants: Vec<(usize, usize)> = self.word_map.iter()
+            .filter(|(word_id, word)| word.is_placed() && crossings.get(word_id) == Some(&1))
+            .map(|(word_id, word)| (*word_id, word.len()))
+            .collect();
+        pendants.sort();
+        pendants
+    }
+
+    /// Classify each filled cell as checked (part of both an across and a down word)
+    /// or unchecked (part of only one word).
+    pub fn cell_checking_map(&self) -> HashMap<Location, CheckStatus> {
+        self.cell_map.iter()
+            .filter(|(_location, cell)| cell.contains_letter())
+            .map(|(location, cell)| {
+                let status = if cell.is_intersection() {
+                    CheckStatus::Checked
+                } else {
+                    CheckStatus::Unchecked
+                };
+                (*location, status)
+            })
+            .collect()
+    }
+
+    /// Fraction of filled cells which are checked. Returns 0.0 if there are no filled cells.
+    pub fn checked_cell_fraction(&self) -> f64 {
+        let checking_map = self.cell_checking_map();
+        if checking_map.is_empty() {
+            0.0
+        } else {
+            let checked = checking_map.values().filter(|s| **s == CheckStatus::Checked).count();
+            checked as f64 / checking_map.len() as f64
+        }
+    }
+
+    /// Average length of the placed words. Returns 0.0 if no words are placed.
+    pub fn average_word_length(&self) -> f64 {
+        let lengths: Vec<usize> = self.word_map.values()
+            .filter(|w| w.is_placed())
+            .map(|w| w.len())
+            .collect();
+        if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        }
+    }
+
+    /// Number of filled cells containing a letter which is rarely used in English,
+    /// such as Q or Z. A letter shared by two words is only counted once.
+    pub fn rare_letter_count(&self) -> usize {
+        self.cell_map.values()
+            .filter(|c| c.contains_letter() && RARE_LETTERS.contains(c.to_char()))
+            .count()
+    }
+
+    /// Rough estimate of how hard the puzzle is to solve, between 0 (easy) and 1 (hard).
+    /// This is the mean of three terms, each clamped to between 0 and 1:
+    ///
+    /// * the fraction of filled cells which are unchecked
+    /// * the average word length, scaled so that 3 letters gives 0 and 12 letters gives 1
+    /// * the number of rare letters per placed word
+    ///
+    /// Returns 0.0 if no words are placed.
+    pub fn difficulty_estimate(&self) -> f64 {
+        let num_placed = self.count_placed_words();
+        if num_placed == 0 {
+            return 0.0;
+        }
+        let unchecked = 1.0 - self.checked_cell_fraction();
+        let length = (self.average_word_length() - 3.0) / 9.0;
+        let rare = self.rare_letter_count() as f64 / num_placed as f64;
+        (unchecked + length.clamp(0.0, 1.0) + rare.clamp(0.0, 1.0)) / 3.0
+    }
+
+    /// Returns true if every filled cell is part of both an across and a down word,
+    /// so that every letter is checked by a crossing answer.
+    pub fn fully_checked(&self) -> bool {
+        self.cell_map.values()
+            .filter(|c| c.contains_letter())
+            .all(|c| c.is_intersection())
+    }
+
+    /// Placed words with at least one cell on the border of the box containing the filled
+    /// cells, ordered by id
+    pub fn words_on_edge(&self) -> Vec<usize> {
+        let (top_left, bottom_right) = match self.filled_bounding_box() {
+            Some(corners) => corners,
+            None => return vec![],
+        };
+        let (first_row, first_col) = (top_left.0, top_left.1);
+        let (last_row, last_col) = (bottom_right.0, bottom_right.1);
+
+        let mut edge_words: Vec<usize> = self.word_map.iter()
+            .filter(|(_id, word)| {
+                word.get_location().is_some_and(|(start, end, _direction)| {
+                    start.0 == first_row || end.0 == last_row || start.1 == first_col || end.1 == last_col
+                })
+            })
+            .map(|(id, _word)| *id)
+            .collect();
+        edge_words.sort();
+        edge_words
+    }
+
+    /// The id and text of the word covering the cell in the given direction, or None if
+    /// the cell is empty, black, outside the grid or only part of a word in the other direction
+    pub fn word_at(&self, location: Location, direction: Direction) -> Option<(usize, String)> {
+        let word_id = self.cell_map.get(&location)?.get_word_id(direction)?;
+        self.word_map.get(&word_id).map(|word| (word_id, word.word_text.clone()))
+    }
+
+    /// Inspect the cell at the given location, or None if the location is outside the grid
+    pub fn cell_at(&self, location: Location) -> Option<PublicCell> {
+        self.cell_map.get(&location).map(|cell| cell.public_view())
+    }
+
+    /// Cells without a letter which are orthogonally adjacent to the word, excluding the
+    /// cells just before its start and just after its end. Ordered by row then column.
+    /// Returns an empty list if the word doesn't exist or isn't placed.
+    pub fn word_frontier(&self, word_id: usize) -> Vec<Location> {
+        let mut frontier: Vec<Location> = vec![];
+        if let Some((start, end, direction)) = self.get_word(word_id).ok().and_then(|w| w.get_location()) {
+            let boundary = [start.relative_location_directed(-1, direction),
+                            end.relative_location_directed(1, direction)];
+            let mut location = start;
+            while location != end.relative_location_directed(1, direction) {
+                let neighbours = [location.relative_location(-1, 0),
+                                  location.relative_location(1, 0),
+                                  location.relative_location(0, -1),
+                                  location.relative_location(0, 1)];
+                for neighbour in neighbours.iter() {
+                    let has_letter = self.cell_map.get(neighbour).is_some_and(|c| c.contains_letter());
+                    if !has_letter && !boundary.contains(neighbour) && !frontier.contains(neighbour) {
+                        frontier.push(*neighbour);
+                    }
+                }
+                location = location.relative_location_directed(1, direction);
+            }
+        }
+        frontier.sort_by_key(|location| (location.0, location.1));
+        frontier
+    }
+
     pub fn get_grid_dimensions_with_buffer(&self) -> (usize, usize) {
         let nrows: usize = (self.bottom_right_cell_index.0 - self.top_left_cell_index.0 + 1) as usize;
         let ncols: usize = (self.bottom_right_cell_index.1 - self.top_left_cell_index.1 + 1) as usize;
@@ -37,15 +261,278 @@ impl CrosswordGrid {
         (nrows, ncols)
     }
 
+    /// Dimensions (nrows, ncols) of the smallest box containing every filled cell, ignoring
+    /// any buffer or empty edges. This is the area used when scoring the grid.
+    /// Returns (0, 0) if there are no filled cells.
+    pub fn scoring_dimensions(&self) -> (usize, usize) {
+        match self.filled_bounding_box() {
+            Some((top_left, bottom_right)) => ((bottom_right.0 - top_left.0 + 1) as usize,
+                                               (bottom_right.1 - top_left.1 + 1) as usize),
+            None => (0, 0),
+        }
+    }
+
+    /// The top-left and bottom-right corners of the smallest box containing every filled
+    /// cell, whatever the buffer around the grid. Returns None if no cells are filled.
+    pub(super) fn filled_bounding_box(&self) -> Option<(Location, Location)> {
+        let filled: Vec<&Location> = self.cell_map.iter()
+            .filter(|(_location, cell)| cell.contains_letter())
+            .map(|(location, _cell)| location)
+            .collect();
+        if filled.is_empty() {
+            return None;
+        }
+        let min_row = filled.iter().map(|location| location.0).min().unwrap();
+        let max_row = filled.iter().map(|location| location.0).max().unwrap();
+        let min_col = filled.iter().map(|location| location.1).min().unwrap();
+        let max_col = filled.iter().map(|location| location.1).max().unwrap();
+        Some((Location(min_row, min_col), Location(max_row, max_col)))
+    }
+
+    /// Returns the (word_id, length) of the longest placed word, with ties broken by the
+    /// lowest word_id. Returns None if no words are placed.
+    pub fn longest_word(&self) -> Option<(usize, usize)> {
+        let mut placed_words: Vec<(usize, usize)> = self.word_map.iter()
+            .filter(|(_id, w)| w.is_placed())
+            .map(|(id, w)| (*id, w.len()))
+            .collect();
+        placed_words.sort_by_key(|(id, length)| (cmp::Reverse(*length), *id));
+        placed_words.first().cloned()
+    }
+
+    /// Returns the eccentricity of the seed word (the placed word with the lowest word_id,
+    /// which is the first word placed by the generator) in the graph of intersecting words.
+    /// A high reach means a stringy grid. Returns 0 if no words are placed.
+    pub fn seed_reach(&self) -> usize {
+        let seed_word_id = self.word_map.iter()
+            .filter(|(_id, w)| w.is_placed())
+            .map(|(id, _w)| *id)
+            .min();
+        match seed_word_id {
+            Some(word_id) => self.to_graph().eccentricity(word_id).expect("Placed words should all be nodes in the graph"),
+            None => 0,
+        }
+    }
+
+    /// Returns the number of words in the longest chain of placed words each crossing the
+    /// next, taking the shortest route between each pair of words. Returns 0 if no words are placed.
+    pub fn longest_word_chain(&self) -> usize {
+        if self.count_placed_words() == 0 {
+            0
+        } else {
+            self.to_graph().longest_path_len() + 1
+        }
+    }
+
+    /// Length of the longest chain of words as a fraction of the number of placed words.
+    /// A stringy grid where each word only crosses the next scores 1, while woven grids
+    /// with many crossings score lower. Returns 0 if no words are placed.
+    pub fn spine_ratio(&self) -> f64 {
+        let words_placed = self.count_placed_words();
+        if words_placed == 0 {
+            0.0
+        } else {
+            self.longest_word_chain() as f64 / words_placed as f64
+        }
+    }
+
+    /// Returns the larger of the number of rows and number of columns in the grid
+    pub fn grid_span(&self) -> usize {
+        let (nrows, ncols) = self.get_grid_dimensions();
+        cmp::max(nrows, ncols)
+    }
+
+    /// A 64-bit hash of the layout of the grid, which is the same for any two grids
+    /// with the same to_string(), so can be stored in place of the full string.
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.to_string().hash(&mut hasher);
+        hasher.finish()
+    }
+
     pub fn count_filled_cells(&self) -> usize {
         self.cell_map.values().filter(|c| c.contains_letter()).count()
     }
 
+    /// Count how many times each letter appears in the filled cells of the grid.
+    /// A letter shared by two words is only counted once.
+    pub fn letter_counts(&self) -> BTreeMap<char, usize> {
+        let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+        for cell in self.cell_map.values().filter(|c| c.contains_letter()) {
+            *counts.entry(cell.to_char()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Count how many times each letter appears at an intersection of an across and a down word.
+    /// Grids crossing mostly on common letters are usually easier to extend.
+    pub fn intersection_letter_histogram(&self) -> BTreeMap<char, usize> {
+        let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+        for cell in self.cell_map.values().filter(|c| c.is_intersection()) {
+            *counts.entry(cell.to_char()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// How central the crossings of the grid are within their words, averaged over both words
+    /// at every intersection, where a crossing at either end of a word scores 0 and one in the
+    /// middle scores 1. Central crossings constrain both words more. Returns 0.0 if there
+    /// are no intersections.
+    pub fn intersection_centrality(&self) -> f64 {
+        let centrality = |word_id: usize, index: isize| {
+            let length = self.word_map.get(&word_id).unwrap().len();
+            if length > 1 {
+                1.0 - (2.0 * index as f64 / (length - 1) as f64 - 1.0).abs()
+            } else {
+                1.0
+            }
+        };
+
+        let mut total = 0.0;
+        let mut num_intersections = 0;
+        for (location, cell) in self.cell_map.iter().filter(|(_location, cell)| cell.is_intersection()) {
+            let across_word_id = cell.get_across_word_id().unwrap();
+            let down_word_id = cell.get_down_word_id().unwrap();
+            let (across_start, _end, _direction) = self.word_map.get(&across_word_id).unwrap().get_location().unwrap();
+            let (down_start, _end, _direction) = self.word_map.get(&down_word_id).unwrap().get_location().unwrap();
+            total += (centrality(across_word_id, location.1 - across_start.1)
+                      + centrality(down_word_id, location.0 - down_start.0)) / 2.0;
+            num_intersections += 1;
+        }
+
+        if num_intersections > 0 {
+            total / num_intersections as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Count the placed words of each (length, direction), e.g. to spot grids made up of
+    /// many words of the same shape.
+    pub fn word_shape_counts(&self) -> BTreeMap<(usize, Direction), usize> {
+        let mut counts: BTreeMap<(usize, Direction), usize> = BTreeMap::new();
+        for word in self.word_map.values() {
+            if let Some((_start, _end, direction)) = word.get_location() {
+                *counts.entry((word.len(), direction)).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Count the cells within the box containing the filled cells which are either filled, or
+    /// empty but not forced to be black by being next to the start or end of a word. Any
+    /// buffer around the grid isn't counted.
+    pub fn playable_cell_count(&self) -> usize {
+        let (top_left, bottom_right) = match self.filled_bounding_box() {
+            Some(corners) => corners,
+            None => return 0,
+        };
+        let mut count = 0;
+        for row in top_left.0..=bottom_right.0 {
+            for col in top_left.1..=bottom_right.1 {
+                if self.cell_map.get(&Location(row, col)).is_some_and(|c| !c.is_black()) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Count how many placed words pass through each row (if counting rows) or column of
+    /// the box containing the filled cells, including words which only cross it
+    fn words_per_line(&self, rows: bool) -> Vec<usize> {
+        let top_left = match self.filled_bounding_box() {
+            Some((top_left, _bottom_right)) => top_left,
+            None => return vec![],
+        };
+        let (nrows, ncols) = self.scoring_dimensions();
+        let mut counts: Vec<usize> = vec![0; if rows { nrows } else { ncols }];
+        let first_line = if rows { top_left.0 } else { top_left.1 };
+        for word in self.word_map.values() {
+            if let Some((start, end, _direction)) = word.get_location() {
+                let (first, last) = if rows { (start.0, end.0) } else { (start.1, end.1) };
+                for line in first..=last {
+                    counts[(line - first_line) as usize] += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Number of placed words passing through each row of the filled area, from top to bottom
+    pub fn words_per_row(&self) -> Vec<usize> {
+        self.words_per_line(true)
+    }
+
+    /// Number of placed words passing through each column of the filled area, from left to right
+    pub fn words_per_col(&self) -> Vec<usize> {
+        self.words_per_line(false)
+    }
+
+    /// Proportion of the playable cells which are filled. Unlike comparing the filled cells
+    /// with the whole grid, black cells aren't counted, so this measures how tightly packed
+    /// the area available for words is, whatever the buffer around the grid. Returns 0 if
+    /// there are no playable cells.
+    pub fn word_fill_efficiency(&self) -> f64 {
+        let playable = self.playable_cell_count();
+        if playable == 0 {
+            0.0
+        } else {
+            self.count_filled_cells() as f64 / playable as f64
+        }
+    }
+
     pub fn count_empty_cells(&self) -> usize {
         let (nrows, ncols) = self.get_grid_dimensions();
         nrows * ncols - self.count_filled_cells()
     }
 
+    /// Average position of the filled cells, as a fraction of the height and width of the
+    /// box containing them, measured to the centre of each cell. A grid which is balanced
+    /// top-to-bottom and left-to-right has centre of mass (0.5, 0.5), the value also
+    /// returned if there are no filled cells.
+    pub fn center_of_mass(&self) -> (f64, f64) {
+        let top_left = match self.filled_bounding_box() {
+            Some((top_left, _bottom_right)) => top_left,
+            None => return (0.5, 0.5),
+        };
+        let (nrows, ncols) = self.scoring_dimensions();
+        let mut total_row: f64 = 0.0;
+        let mut total_col: f64 = 0.0;
+        let mut filled: f64 = 0.0;
+        for location in self.cell_map.iter().filter(|(_l, c)| c.contains_letter()).map(|(l, _c)| l) {
+            total_row += (location.0 - top_left.0) as f64 + 0.5;
+            total_col += (location.1 - top_left.1) as f64 + 0.5;
+            filled += 1.0;
+        }
+        (total_row / filled / nrows as f64, total_col / filled / ncols as f64)
+    }
+
+    /// Ratio of the number of filled cells to the number of filled cells on the
+    /// perimeter of the filled region, i.e. those next to a cell without a letter.
+    /// Blockier grids have more interior cells, so score higher. Returns 0.0 if there
+    /// are no filled cells.
+    pub fn compactness(&self) -> f64 {
+        let mut filled: usize = 0;
+        let mut perimeter: usize = 0;
+        for location in self.cell_map.iter().filter(|(_l, c)| c.contains_letter()).map(|(l, _c)| l) {
+            filled += 1;
+            let neighbours = [location.relative_location(-1, 0),
+                              location.relative_location(1, 0),
+                              location.relative_location(0, -1),
+                              location.relative_location(0, 1)];
+            let on_perimeter = neighbours.iter().any(|n| !self.cell_map.get(n).is_some_and(|c| c.contains_letter()));
+            if on_perimeter {
+                perimeter += 1;
+            }
+        }
+        if perimeter == 0 {
+            0.0
+        } else {
+            filled as f64 / perimeter as f64
+        }
+    }
+
     pub fn average_intersections_per_word(&self) -> f64 {
         let mut percent_intersection_per_word: Vec<f64> = vec![];
         for word in self.word_map.values() {
@@ -69,6 +556,11 @@ impl CrosswordGrid {
             }
         }
         debug!("{:?}", percent_intersection_per_word);
-        percent_intersection_per_word.iter().sum::<f64>() / (percent_intersection_per_word.len() as f64)
+        if percent_intersection_per_word.is_empty() {
+            // No placed words, so avoid returning the NaN mean of an empty list
+            0.0
+        } else {
+            percent_intersection_per_word.iter().sum::<f64>() / (percent_intersection_per_word.len() as f64)
+        }
     }
 }
@@ -1,5 +1,6 @@
 use log::debug;
-use std::collections::HashMap;
+use std::cmp;
+use std::collections::{HashMap,HashSet};
 
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
@@ -8,10 +9,17 @@ use rand::rngs::StdRng;
 use super::CrosswordGrid;
 use super::Location;
 use super::Direction;
+use super::EnumerationStyle;
 
 use super::Word;
+use super::Cell;
 use super::VALID_ANSWERCHARS;
 
+use crate::generator::CrosswordGeneratorSettings;
+
+/// Number of pairs of starting words tried by balanced_partition
+const BALANCED_PARTITION_ATTEMPTS: usize = 10;
+
 #[derive(Debug,Clone)]
 struct PlacementAttempt {
     word_id: usize,
@@ -32,6 +40,12 @@ struct PlacementAttemptIterator {
 
 impl PlacementAttemptIterator {
     fn new(grid: &CrosswordGrid, seed: u64) -> Self {
+        PlacementAttemptIterator::new_with_letter_filter(grid, seed, None)
+    }
+
+    /// Iterator over placement attempts which only cross the existing grid on
+    /// one of the given letters, or on any letter if there is no filter
+    fn new_with_letter_filter(grid: &CrosswordGrid, seed: u64, letter_filter: Option<&str>) -> Self {
         let mut rng = StdRng::seed_from_u64(seed);
         let empty_word = Word::new_unplaced("", "", None);
 
@@ -50,6 +64,14 @@ impl PlacementAttemptIterator {
             letter_to_locations.get_mut(&letter).unwrap().push((*location, empty_direction));
         }
 
+        if let Some(letters) = letter_filter {
+            for (letter, locations) in letter_to_locations.iter_mut() {
+                if !letters.contains(*letter) {
+                    locations.clear();
+                }
+            }
+        }
+
         for c in VALID_ANSWERCHARS.chars() {
             letter_to_locations.get_mut(&c).unwrap().sort_by_key(|a| (a.1, a.0.0, a.0.1));
             letter_to_locations.get_mut(&c).unwrap().shuffle(&mut rng);
@@ -75,6 +97,23 @@ impl PlacementAttemptIterator {
         }
     }
 
+    /// Iterator over placement attempts which tries the longest words first, since these
+    /// give the most chances for later words to cross them. Words of the same length are
+    /// still tried in the shuffled order.
+    fn new_longest_first(grid: &CrosswordGrid, seed: u64) -> Self {
+        let mut iterator = PlacementAttemptIterator::new(grid, seed);
+        // Words are popped from the end of the list, so put the longest last
+        iterator.words.sort_by_key(|(_id, word)| word.len());
+        iterator
+    }
+
+    /// Iterator over placement attempts for just the given word
+    fn new_single_word(grid: &CrosswordGrid, word_id: usize, seed: u64) -> Self {
+        let mut iterator = PlacementAttemptIterator::new(grid, seed);
+        iterator.words.retain(|(id, _word)| *id == word_id);
+        iterator
+    }
+
     fn get_all_locations_current(&mut self) {
         self.remaining_locations = self.letter_to_locations
             .get(&self.current_word.get_char_at_index(self.current_index_in_word))
@@ -155,26 +194,250 @@ impl Iterator for PlacementAttemptIterator {
 
 impl CrosswordGrid {
     pub fn place_random_word(&mut self, seed: u64) -> bool {
-        let mut success = false;
-        let mut keep_going = true;
-        let mut attempt_iterator = PlacementAttemptIterator::new(&self, seed);
-        while !success && keep_going {
-            if let Some(attempt) = attempt_iterator.next() {
-                let result = self.place_word_in_cell(attempt.location,
-                                                     attempt.word_id,
-                                                     attempt.index_in_word,
-                                                     attempt.direction);
-                success = result.is_ok();
-            } else {
-                // Out of possible placements to try!
-                keep_going = false;
+        self.place_random_word_counted(seed).0
+    }
+
+    /// Attempt to place a random word, as place_random_word, also returning the number of
+    /// placement attempts tried. If no placement succeeds this is the total number of attempts.
+    pub fn place_random_word_counted(&mut self, seed: u64) -> (bool, usize) {
+        let attempt_iterator = PlacementAttemptIterator::new(self, seed);
+        self.place_random_word_limited(attempt_iterator, None, false)
+    }
+
+    /// Attempt to place a random word, as place_random_word, but give up after max_attempts
+    /// placements have been tried even if there are others left to try. This gives a
+    /// predictable worst-case time on dense grids, at the cost of sometimes missing a
+    /// valid placement.
+    pub fn place_random_word_bounded(&mut self, seed: u64, max_attempts: usize) -> bool {
+        let attempt_iterator = PlacementAttemptIterator::new(self, seed);
+        self.place_random_word_limited(attempt_iterator, Some(max_attempts), false).0
+    }
+
+    /// Try the placements from the iterator in order until one succeeds, there are none left,
+    /// or the optional limit on the number of attempts is reached. If forbid_squares is set,
+    /// placements which create a 2x2 block of filled cells are undone and count as failures.
+    /// Returns whether a word was placed and the number of attempts tried.
+    fn place_random_word_limited(&mut self,
+                                 attempt_iterator: PlacementAttemptIterator,
+                                 max_attempts: Option<usize>,
+                                 forbid_squares: bool) -> (bool, usize) {
+        let mut attempts_tried: usize = 0;
+        for attempt in attempt_iterator {
+            if max_attempts.is_some_and(|max_attempts| attempts_tried >= max_attempts) {
+                debug!("Giving up placing a word after {} attempts", attempts_tried);
+                break;
+            }
+            attempts_tried += 1;
+            let result = self.place_word_in_cell(attempt.location,
+                                                 attempt.word_id,
+                                                 attempt.index_in_word,
+                                                 attempt.direction);
+            if result.is_ok() {
+                if forbid_squares && self.solid_square_around_word(attempt.word_id) {
+                    debug!("Rejecting placement {:?} since it creates a solid square", attempt);
+                    self.unplace_word(attempt.word_id);
+                    self.fill_black_cells();
+                } else {
+                    return (true, attempts_tried);
+                }
             }
         }
+        (false, attempts_tried)
+    }
+
+    /// Attempt to place a random word, as place_random_word, but only crossing the
+    /// existing grid on one of the given letters e.g. on vowels, which are usually
+    /// easier to cross again later.
+    pub fn place_random_word_crossing_on(&mut self, letters: &str, seed: u64) -> bool {
+        let attempt_iterator = PlacementAttemptIterator::new_with_letter_filter(self, seed, Some(letters));
+        self.place_random_word_limited(attempt_iterator, None, false).0
+    }
+
+    /// Attempt to place a random word, as place_random_word, but trying the longest
+    /// unplaced words first.
+    pub fn place_random_word_longest_first(&mut self, seed: u64) -> bool {
+        self.place_random_word_with_options(seed, true, false)
+    }
+
+    /// Attempt to place a random word, as place_random_word, but rejecting any placement
+    /// which would create a 2x2 block of filled cells.
+    pub fn place_random_word_forbidding_squares(&mut self, seed: u64) -> bool {
+        self.place_random_word_with_options(seed, false, true)
+    }
+
+    /// Attempt to place a random word, optionally trying the longest unplaced words first
+    /// and optionally rejecting placements which would create a 2x2 block of filled cells.
+    /// The two options can be combined.
+    pub(crate) fn place_random_word_with_options(&mut self, seed: u64, longest_first: bool, forbid_squares: bool) -> bool {
+        let attempt_iterator = if longest_first {
+            PlacementAttemptIterator::new_longest_first(self, seed)
+        } else {
+            PlacementAttemptIterator::new(self, seed)
+        };
+        self.place_random_word_limited(attempt_iterator, None, forbid_squares).0
+    }
+
+    /// Estimate how much more the grid could grow, by greedily placing random unplaced words
+    /// into a copy of the grid until none fit, and returning the number placed.
+    /// This clones the grid and tries every remaining placement attempt once for each word
+    /// placed, so is expensive for large word lists. The grid itself is not changed.
+    pub fn remaining_capacity(&self, seed: u64) -> usize {
+        let mut copied = self.clone();
+        let mut num_placed = 0;
+        while copied.place_random_word(seed.wrapping_add(num_placed as u64)) {
+            num_placed += 1;
+        }
+        num_placed
+    }
+
+    /// Attempt to place a random word, keeping the placement only if it increases the
+    /// summary score under the given settings. Otherwise the grid is left unchanged.
+    pub fn place_random_word_if_improves(&mut self, settings: &CrosswordGeneratorSettings, seed: u64) -> bool {
+        let score_before = settings.score_summary(self);
+        let mut candidate = self.clone();
+        if candidate.place_random_word(seed) && settings.score_summary(&candidate) > score_before {
+            *self = candidate;
+            true
+        } else {
+            false
+        }
+    }
 
+    /// Replace every empty cell in the playable area with a random letter, which doesn't belong
+    /// to any word, e.g. for a word search. Filled cells and black cells are left unchanged.
+    /// The letters chosen are deterministic given the seed.
+    pub fn fill_empties_with_random_letters(&mut self, seed: u64) {
+        // Placing a word doesn't mark the cells just past its ends as black, so do that first
+        self.fill_black_cells();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let letters: Vec<char> = VALID_ANSWERCHARS.chars().collect();
+        for row in (self.top_left_cell_index.0 + 1)..self.bottom_right_cell_index.0 {
+            for col in (self.top_left_cell_index.1 + 1)..self.bottom_right_cell_index.1 {
+                let cell = self.cell_map.get_mut(&Location(row, col)).unwrap();
+                if cell.is_empty() {
+                    *cell = Cell::new(*letters.choose(&mut rng).unwrap(), None, None);
+                }
+            }
+        }
+    }
+
+    /// Count the unplaced words which have at least one valid placement connecting them
+    /// to the existing grid. The grid itself is left unchanged.
+    pub fn connectable_unplaced_count(&self, seed: u64) -> usize {
+        let mut unplaced_word_ids: Vec<usize> = self.word_map.iter()
+            .filter(|(_id, w)| !w.is_placed())
+            .map(|(id, _w)| *id)
+            .collect();
+        unplaced_word_ids.sort();
+
+        unplaced_word_ids.iter()
+            .filter(|word_id| {
+                PlacementAttemptIterator::new_single_word(self, **word_id, seed).any(|attempt| {
+                    let mut candidate = self.clone();
+                    candidate.place_word_in_cell(attempt.location,
+                                                 attempt.word_id,
+                                                 attempt.index_in_word,
+                                                 attempt.direction).is_ok()
+                })
+            })
+            .count()
+    }
+
+    /// Count the distinct valid placements of the word which connect it to the existing grid.
+    /// The grid itself is left unchanged.
+    fn count_valid_placements(&self, word_id: usize, seed: u64) -> usize {
+        let mut placements: HashSet<(Location, Direction)> = HashSet::new();
+        for attempt in PlacementAttemptIterator::new_single_word(self, word_id, seed) {
+            let mut candidate = self.clone();
+            if candidate.place_word_in_cell(attempt.location,
+                                            attempt.word_id,
+                                            attempt.index_in_word,
+                                            attempt.direction).is_ok() {
+                if let Some((start_location, _end_location, direction)) = candidate.word_map.get(&word_id).unwrap().get_location() {
+                    placements.insert((start_location, direction));
+                }
+            }
+        }
+        placements.len()
+    }
+
+    /// Find the unplaced word with the fewest valid placements connecting it to the existing
+    /// grid, following the minimum-remaining-values heuristic. Ties are broken by picking the
+    /// longest word, then the lowest word_id. A word with no valid placement counts as the
+    /// most constrained, since it means the grid can't be completed.
+    /// Returns None if there are no unplaced words.
+    pub fn most_constrained_unplaced(&self, seed: u64) -> Option<usize> {
+        self.word_map.iter()
+            .filter(|(_id, w)| !w.is_placed())
+            .map(|(id, w)| (self.count_valid_placements(*id, seed), cmp::Reverse(w.len()), *id))
+            .min()
+            .map(|(_num_placements, _len, word_id)| word_id)
+    }
+
+    /// Try every valid placement of the word which connects to the existing grid, and keep
+    /// the one which creates the most new intersections. Ties are broken by picking the
+    /// placement whose start is nearest the top-left, with across before down.
+    /// Returns false if there is no valid placement.
+    pub fn place_word_best(&mut self, word_id: usize) -> bool {
+        let intersections_before = self.count_intersections();
+        let mut best_key = None;
+        let mut best_grid: Option<CrosswordGrid> = None;
+
+        for attempt in PlacementAttemptIterator::new_single_word(self, word_id, 0) {
+            let mut candidate = self.clone();
+            let result = candidate.place_word_in_cell(attempt.location,
+                                                      attempt.word_id,
+                                                      attempt.index_in_word,
+                                                      attempt.direction);
+            if result.is_ok() {
+                let new_intersections = candidate.count_intersections() - intersections_before;
+                let (start, _end, direction) = candidate.get_word(word_id)
+                    .ok()
+                    .and_then(|w| w.get_location())
+                    .expect("Word was just placed successfully");
+                let key = (new_intersections, cmp::Reverse((start.0, start.1, direction)));
+                debug!("Placement {:?} would add {} intersections", attempt, new_intersections);
+                if best_key.is_none() || Some(key) > best_key {
+                    best_key = Some(key);
+                    best_grid = Some(candidate);
+                }
+            }
+        }
+
+        if let Some(grid) = best_grid {
+            *self = grid;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Attempt to place a random word. If no word can be placed, remove a random
+    /// leaf and retry once, since a single leaf word can block the only valid
+    /// placement of another word. If the retry also fails, the grid is left unchanged.
+    pub fn place_random_word_or_prune(&mut self, seed: u64) -> bool {
+        let mut success = self.place_random_word(seed);
+        if !success && self.count_placed_words() > 1 {
+            let mut pruned = self.clone();
+            let removed_word_ids = pruned.remove_random_leaves(1, seed);
+
+            // Hide the pruned leaves while retrying, so that we don't just put them straight back
+            let removed_words: Vec<(usize, Word)> = removed_word_ids.iter()
+                .map(|word_id| (*word_id, pruned.word_map.remove(word_id).unwrap()))
+                .collect();
+            success = pruned.place_random_word(seed);
+            pruned.word_map.extend(removed_words);
+
+            if success {
+                *self = pruned;
+            }
+        }
         success
     }
 
-    pub fn remove_random_leaves(&mut self, num_leaves: usize, seed: u64) {
+    /// Remove up to num_leaves random leaf words from the grid, returning the ids of
+    /// the words which were removed.
+    pub fn remove_random_leaves(&mut self, num_leaves: usize, seed: u64) -> Vec<usize> {
         let mut leaves: Vec<usize> = self.to_graph().find_leaves();
         let mut rng = StdRng::seed_from_u64(seed);
         leaves.sort();
@@ -182,31 +445,38 @@ impl CrosswordGrid {
 
         debug!("Attempting to remove {} leaves", num_leaves);
 
+        let mut removed: Vec<usize> = vec![];
         let mut count: usize = 0;
         while count < num_leaves && self.count_placed_words() > 1 {
             if let Some(word_id) = leaves.pop() {
                 debug!("Removing leaf word {}", word_id);
                 self.unplace_word(word_id);
+                removed.push(word_id);
             }
             count += 1;
         }
+        removed
     }
 
     /// For each word in the word list, generates a grid where only that word is placed
     /// Direction is chosen randomly from valid directions for the word
     /// All other words are left unplaced
-    pub fn random_singleton_grids(words: Vec<&str>, seed: u64) -> Vec<Self> {
+    /// Word lengths are added to clues which lack them using the given enumeration style
+    pub fn random_singleton_grids(words: Vec<&str>, seed: u64, enumeration_style: EnumerationStyle) -> Vec<Self> {
+        let parsed_words: Vec<(usize, Word)> = words.iter()
+            .enumerate()
+            .filter_map(|(word_id, word_str)| Word::new_parsed_with_style(word_str, enumeration_style).ok().map(|word| (word_id, word)))
+            .collect();
+        CrosswordGrid::random_singleton_grids_from_words(parsed_words, seed)
+    }
+
+    /// As random_singleton_grids, but from words which have already been parsed,
+    /// each given with the word_id it should have in the grids
+    pub(crate) fn random_singleton_grids_from_words(words: Vec<(usize, Word)>, seed: u64) -> Vec<Self> {
         let mut rng: StdRng = StdRng::seed_from_u64(seed);
         let mut singletons: Vec<Self> = vec![];
-        let mut word_ids: Vec<usize> = vec![];
-
-        let mut word_map: HashMap<usize, Word> = HashMap::new();
-        for (word_id, word_str) in words.iter().enumerate() {
-            if let Ok(word) = Word::new_parsed(word_str) {
-                word_map.insert(word_id, word);
-                word_ids.push(word_id);
-            }
-        }
+        let word_ids: Vec<usize> = words.iter().map(|(word_id, _word)| *word_id).collect();
+        let word_map: HashMap<usize, Word> = words.into_iter().collect();
 
         for word_id in word_ids.iter() {
             let word = word_map.get(word_id).unwrap();
@@ -224,26 +494,66 @@ impl CrosswordGrid {
         singletons
     }
 
-    pub fn random_partition(&mut self, seed: u64) -> Self {
-        let mut rng = StdRng::seed_from_u64(seed);
+    /// Placed word ids, sorted and then shuffled using the seeded RNG
+    fn shuffled_placed_word_ids(&self, rng: &mut StdRng) -> Vec<usize> {
         let mut word_ids: Vec<usize> = self.word_map.iter().filter_map(|(k,v)| {
             if v.is_placed() {
                 Some(k.clone())
             } else {
                 None
             }}).collect();
-        self.fit_to_size();
         word_ids.sort();
-        word_ids.shuffle(&mut rng);
+        word_ids.shuffle(rng);
         assert!(word_ids.len() > 1,
                 "Expecting at least two nodes to be able to partition the graph. Word ids: {:?}", word_ids);
+        word_ids
+    }
+
+    pub fn random_partition(&mut self, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.fit_to_size();
+        let word_ids = self.shuffled_placed_word_ids(&mut rng);
 
-        let first_node = word_ids[0];
-        let second_node = word_ids[1];
         let (first_node_vec, second_node_vec) = self.to_graph()
-            .partition_nodes(first_node, second_node)
+            .partition_nodes(word_ids[0], word_ids[1])
             .expect("Nodes should exist, as they correspond to words in the word_map of the grid");
+        self.split_by_partition(first_node_vec, second_node_vec)
+    }
 
+    /// Partition the grid as random_partition, but try several pairs of starting words and keep
+    /// the split where the two halves are closest in size. The first pair tried is the one
+    /// random_partition would use with the same seed, so the split is never less balanced.
+    pub fn balanced_partition(&mut self, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.fit_to_size();
+        let word_ids = self.shuffled_placed_word_ids(&mut rng);
+        let graph = self.to_graph();
+
+        let mut best_partition: Option<(Vec<usize>, Vec<usize>)> = None;
+        let mut best_difference = usize::MAX;
+        let mut node_pair = (word_ids[0], word_ids[1]);
+        for _ in 0..BALANCED_PARTITION_ATTEMPTS {
+            let (first_node_vec, second_node_vec) = graph
+                .partition_nodes(node_pair.0, node_pair.1)
+                .expect("Nodes should exist, as they correspond to words in the word_map of the grid");
+            let difference = (first_node_vec.len() as isize - second_node_vec.len() as isize).unsigned_abs();
+            debug!("Partition from nodes {:?} has size difference {}", node_pair, difference);
+            if difference < best_difference {
+                best_difference = difference;
+                best_partition = Some((first_node_vec, second_node_vec));
+            }
+
+            let chosen: Vec<usize> = word_ids.choose_multiple(&mut rng, 2).cloned().collect();
+            node_pair = (chosen[0], chosen[1]);
+        }
+
+        let (first_node_vec, second_node_vec) = best_partition.expect("At least one partition should have been tried");
+        self.split_by_partition(first_node_vec, second_node_vec)
+    }
+
+    /// Keep the words in the first vector in this grid, and return a grid containing the words
+    /// in the second vector
+    fn split_by_partition(&mut self, first_node_vec: Vec<usize>, second_node_vec: Vec<usize>) -> Self {
         let mut second_grid = self.clone();
         for word_id in first_node_vec {
             second_grid.unplace_word(word_id);
@@ -264,8 +574,204 @@ impl CrosswordGrid {
 mod tests {
     use super::*;
     use super::super::CrosswordGridBuilder;
+    use super::super::CrosswordError;
     use log::info;
 
+    #[test]
+    fn test_fill_empties_with_random_letters() {
+        crate::logging::init_logger(true);
+        let original = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let mut grid = original.clone();
+        grid.fill_empties_with_random_letters(13);
+
+        for (location, cell) in original.cell_map.iter() {
+            let filled_cell = grid.cell_map.get(location).unwrap();
+            if cell.contains_letter() {
+                assert_eq!(filled_cell.to_char(), cell.to_char());
+                assert_eq!(filled_cell.get_across_word_id(), cell.get_across_word_id());
+                assert_eq!(filled_cell.get_down_word_id(), cell.get_down_word_id());
+            } else if cell.is_black() {
+                assert!(filled_cell.is_black());
+            }
+        }
+        assert_eq!(grid.count_filled_cells(), original.playable_cell_count());
+        assert_eq!(grid.to_graph().count_cycles(), original.to_graph().count_cycles());
+
+        let mut same_seed = original.clone();
+        same_seed.fill_empties_with_random_letters(13);
+        assert_eq!(same_seed.to_string(), grid.to_string());
+        let mut other_seed = original.clone();
+        other_seed.fill_empties_with_random_letters(14);
+        assert_ne!(other_seed.to_string(), grid.to_string());
+
+        // The cell just past the end of AXE is only marked black once the black cells are
+        // filled again, but must not be given a letter
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let arrival_word_id = grid.add_unplaced_word("ARRIVAL", "", None);
+        let axe_word_id = grid.add_unplaced_word("AXE", "", None);
+        grid.place_word_in_cell(Location(0, 0), arrival_word_id, 0, Direction::Down).unwrap();
+        grid.place_word_in_cell(Location(0, 4), axe_word_id, 0, Direction::Down).unwrap();
+        grid.fill_empties_with_random_letters(13);
+        assert!(grid.cell_map.get(&Location(3, 4)).unwrap().is_black());
+        assert_eq!(grid.word_at(Location(1, 4), Direction::Down), Some((axe_word_id, "AXE".to_string())));
+    }
+
+    #[test]
+    fn test_letter_filter_iterator() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.add_unplaced_word("PEAR", "", None);
+        grid.add_unplaced_word("HALL", "", None);
+
+        let unrestricted = PlacementAttemptIterator::new(&grid, 13).count();
+        let vowels_only = PlacementAttemptIterator::new_with_letter_filter(&grid, 13, Some("AEIOU")).count();
+        assert!(vowels_only < unrestricted);
+        assert!(vowels_only > 0);
+        for attempt in PlacementAttemptIterator::new_with_letter_filter(&grid, 13, Some("AEIOU")) {
+            assert_eq!(grid.get_cell(&attempt.location).unwrap().to_char(), 'A');
+        }
+        assert_eq!(PlacementAttemptIterator::new_with_letter_filter(&grid, 13, Some("")).count(), 0);
+
+        assert!(grid.place_random_word_crossing_on("AEIOU", 13));
+        assert!(!grid.clone().place_random_word_crossing_on("Z", 13));
+    }
+
+    #[test]
+    fn test_longest_first_iterator() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.add_unplaced_word("PEA", "", None);
+        grid.add_unplaced_word("HAPPY", "", None);
+        grid.add_unplaced_word("LA", "", None);
+        grid.add_unplaced_word("PLAN", "", None);
+        grid.add_unplaced_word("HALL", "", None);
+
+        for seed in 13..16 {
+            let lengths: Vec<usize> = PlacementAttemptIterator::new_longest_first(&grid, seed)
+                .map(|attempt| grid.get_word(attempt.word_id).unwrap().len())
+                .collect();
+            assert_eq!(lengths.len(), PlacementAttemptIterator::new(&grid, seed).count());
+            assert_eq!(lengths[0], 5);
+            assert!(lengths.windows(2).all(|pair| pair[0] >= pair[1]), "Lengths out of order: {:?}", lengths);
+        }
+
+        let mut placed = grid.clone();
+        assert!(placed.place_random_word_longest_first(13));
+        assert_eq!(placed.unplaced_answers(), vec!["PEA", "LA", "PLAN", "HALL"]);
+    }
+
+    #[test]
+    fn test_place_random_word_forbidding_squares() {
+        crate::logging::init_logger(true);
+        // Removing CD from the square leaves its letters in place, as part of the down words,
+        // so the only placement for CD completes a 2x2 block of crossing words
+        let mut grid = CrosswordGridBuilder::new().from_string("AB\nCD\n");
+        let cd_word_id = grid.get_cell(&Location(1, 0)).unwrap().get_across_word_id().unwrap();
+        grid.unplace_word(cd_word_id);
+        assert_eq!(grid.count_placed_words(), 3);
+
+        let original = grid.clone();
+        assert!(!grid.place_random_word_forbidding_squares(13));
+        assert_eq!(grid.count_placed_words(), 3);
+        assert_eq!(grid.to_string(), original.to_string());
+
+        let mut grid = original;
+        assert!(grid.place_random_word(13));
+        assert_eq!(grid.count_placed_words(), 4);
+        assert!(grid.solid_square_around_word(cd_word_id));
+
+        // Ordinary placements are still allowed
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.add_unplaced_word("PEAR", "", None);
+        assert!(grid.place_random_word_forbidding_squares(13));
+        assert!(!grid.solid_square_around_word(0));
+    }
+
+    #[test]
+    fn test_place_random_word_counted() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.add_unplaced_word("PEAR", "", None);
+        let (success, attempts_tried) = grid.place_random_word_counted(13);
+        assert!(success);
+        assert!(attempts_tried > 0);
+        assert_eq!(grid.count_unplaced_words(), 0);
+
+        // The only place BX could cross is down from the B, which would put X next to C
+        let mut grid = CrosswordGridBuilder::new().from_string("AB\nC \n");
+        grid.add_unplaced_word("BX", "", None);
+        let total_attempts = PlacementAttemptIterator::new(&grid, 13).count();
+        assert!(total_attempts > 0);
+        assert_eq!(grid.place_random_word_counted(13), (false, total_attempts));
+        assert_eq!(grid.count_unplaced_words(), 1);
+    }
+
+    #[test]
+    fn test_place_random_word_bounded() {
+        crate::logging::init_logger(true);
+        // The first attempt for BXB puts X next to C, the second succeeds
+        let mut grid = CrosswordGridBuilder::new().from_string("AB\nC \n");
+        grid.add_unplaced_word("BXB", "", None);
+        assert_eq!(grid.clone().place_random_word_counted(13), (true, 2));
+        let before = grid.to_string();
+        assert!(!grid.place_random_word_bounded(13, 1));
+        assert_eq!(grid.to_string(), before);
+        assert_eq!(grid.count_unplaced_words(), 1);
+        assert!(grid.place_random_word_bounded(13, 2));
+        assert_eq!(grid.count_unplaced_words(), 0);
+
+        // ABBA can never be placed, so every attempt is tried unless capped
+        let mut grid = CrosswordGridBuilder::new().from_string("AB\nC \n");
+        grid.add_unplaced_word("ABBA", "", None);
+        for (max_attempts, expected) in [(1, (false, 1)), (10, (false, 2)), (0, (false, 0))].iter() {
+            let attempt_iterator = PlacementAttemptIterator::new(&grid, 13);
+            assert_eq!(grid.place_random_word_limited(attempt_iterator, Some(*max_attempts), false), *expected);
+        }
+    }
+
+    #[test]
+    fn test_remaining_capacity() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.add_unplaced_word("PEAR", "", None);
+        grid.add_unplaced_word("HALL", "", None);
+        grid.add_unplaced_word("XYZ", "", None);
+        let original = grid.to_string();
+
+        // XYZ shares no letters with the other words, so can never be placed
+        let capacity = grid.remaining_capacity(13);
+        assert!(capacity <= grid.count_unplaced_words());
+        assert_eq!(capacity, 2);
+        assert_eq!(grid.to_string(), original);
+        assert_eq!(grid.count_unplaced_words(), 3);
+
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.remaining_capacity(13), 0);
+    }
+
+    #[test]
+    fn test_place_random_word_if_improves() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ABC");
+        grid.add_unplaced_word("AQQQQQQ", "", None);
+        let original = grid.to_string();
+
+        // Only penalise non-square grids, so the only possible placement makes the score worse
+        let mut settings_map: HashMap<&str, usize> = HashMap::new();
+        for setting in ["weight-prop-filled", "weight-prop-intersect", "weight-num-cycles", "weight-num-intersect",
+                        "weight-avg-intersect", "weight-words-placed"].iter() {
+            settings_map.insert(setting, 0);
+        }
+        let settings = CrosswordGeneratorSettings::new_from_hashmap(settings_map);
+        assert!(!grid.place_random_word_if_improves(&settings, 13));
+        assert_eq!(grid.to_string(), original);
+        assert_eq!(grid.count_unplaced_words(), 1);
+
+        // With the default weights, placing another word is worth the extra penalty
+        assert!(grid.place_random_word_if_improves(&CrosswordGeneratorSettings::default(), 13));
+        assert_eq!(grid.count_unplaced_words(), 0);
+    }
+
     #[test]
     fn test_simple_iterator() {
         crate::logging::init_logger(true);
@@ -297,7 +803,7 @@ mod tests {
     #[test]
     fn test_iterator() {
         crate::logging::init_logger(true);
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
         grid.add_unplaced_word("ABACUS", "", None);
         assert_eq!(PlacementAttemptIterator::new(&grid, 13).count(), 9*2 + 1);
         grid.add_unplaced_word("LOOP", "", None);
@@ -307,13 +813,13 @@ mod tests {
     #[test]
     fn test_use_attempts() {
         crate::logging::init_logger(true);
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
         grid.add_unplaced_word("ABACUS", "", None);
         grid.add_unplaced_word("LOOP", "", None);
         grid.add_unplaced_word("BEE", "", None);
         assert_eq!(count_successful_attempts(&grid), 5);
 
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt").unwrap();
         grid.add_unplaced_word("PROBONO", "", None);
         grid.add_unplaced_word("PASTURE", "", None);
         grid.add_unplaced_word("VETO", "", None);
@@ -323,6 +829,118 @@ mod tests {
         assert_eq!(count_successful_attempts(&grid), 2 + 5 + 3 + 5);
     }
 
+    #[test]
+    fn test_place_random_word_or_prune() -> Result<(), CrosswordError> {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let axe_word_id = grid.add_unplaced_word("AXE", "", None);
+        grid.place_word_in_cell(Location(0, 0), axe_word_id, 0, Direction::Down)?;
+
+        // OLD can only cross ALPHA at the L, but then the D would sit next to the X of AXE
+        let old_word_id = grid.add_unplaced_word("OLD", "", None);
+        let before_failure = grid.to_string();
+        assert!(!grid.clone().place_random_word(13));
+
+        assert!(grid.place_random_word_or_prune(13));
+        assert!(grid.get_word(old_word_id)?.is_placed());
+        assert_ne!(before_failure, grid.to_string());
+        grid.check_valid();
+        Ok(())
+    }
+
+    #[test]
+    fn test_place_word_best() -> Result<(), CrosswordError> {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let ale_word_id = grid.add_unplaced_word("ALE", "", None);
+        let axe_word_id = grid.add_unplaced_word("AXE", "", None);
+        grid.place_word_in_cell(Location(0, 0), ale_word_id, 0, Direction::Down)?;
+        grid.place_word_in_cell(Location(0, 4), axe_word_id, 0, Direction::Down)?;
+
+        // EMOTE could hang off either E, but the best placement crosses both
+        let emote_word_id = grid.add_unplaced_word("EMOTE", "", None);
+        let intersections_before = grid.count_intersections();
+        assert!(grid.place_word_best(emote_word_id));
+        grid.check_valid();
+        assert_eq!(grid.count_intersections(), intersections_before + 2);
+        assert_matches!(grid.get_word(emote_word_id)?.get_location(),
+                        Some((Location(2, 0), Location(2, 4), Direction::Across)));
+
+        let quiz_word_id = grid.add_unplaced_word("QUIZ", "", None);
+        let before_failure = grid.to_string();
+        assert!(!grid.place_word_best(quiz_word_id));
+        assert_eq!(before_failure, grid.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_connectable_unplaced_count() -> Result<(), CrosswordError> {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let ale_word_id = grid.add_unplaced_word("ALE", "", None);
+        grid.place_word_in_cell(Location(0, 0), ale_word_id, 0, Direction::Down)?;
+        assert_eq!(grid.connectable_unplaced_count(13), 0);
+
+        // PLUM and LAD share letters with free cells, QUIZ and WORD share no letters at all
+        grid.add_unplaced_word("PLUM", "", None);
+        grid.add_unplaced_word("QUIZ", "", None);
+        grid.add_unplaced_word("LAD", "", None);
+        grid.add_unplaced_word("WORD", "", None);
+        let before = grid.to_string();
+        assert_eq!(grid.connectable_unplaced_count(13), 2);
+        assert_eq!(grid.connectable_unplaced_count(14), 2);
+        assert_eq!(before, grid.to_string());
+        assert_eq!(grid.count_unplaced_words(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_most_constrained_unplaced() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        assert_eq!(grid.most_constrained_unplaced(13), None);
+
+        // JAZZ can only cross on one of the two As, while HAPPY can also cross on the P or H
+        let happy_word_id = grid.add_unplaced_word("HAPPY", "", None);
+        let jazz_word_id = grid.add_unplaced_word("JAZZ", "", None);
+        assert_eq!(grid.count_valid_placements(jazz_word_id, 13), 2);
+        assert!(grid.count_valid_placements(happy_word_id, 13) > 2);
+        assert_eq!(grid.most_constrained_unplaced(13), Some(jazz_word_id));
+        assert_eq!(grid.most_constrained_unplaced(14), Some(jazz_word_id));
+
+        // Ties are broken by length, so the longer ZEBRA beats JAZZ
+        let zebra_word_id = grid.add_unplaced_word("ZEBRA", "", None);
+        assert_eq!(grid.count_valid_placements(zebra_word_id, 13), 2);
+        assert_eq!(grid.most_constrained_unplaced(13), Some(zebra_word_id));
+
+        // A word which can't be placed at all is the most constrained
+        let quiz_word_id = grid.add_unplaced_word("QUIZ", "", None);
+        assert_eq!(grid.most_constrained_unplaced(13), Some(quiz_word_id));
+    }
+
+    #[test]
+    fn test_balanced_partition() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let num_placed = grid.count_placed_words();
+
+        for seed in 0..10 {
+            let mut naive = grid.clone();
+            let naive_other = naive.random_partition(seed);
+            let mut balanced = grid.clone();
+            let balanced_other = balanced.balanced_partition(seed);
+
+            assert_eq!(balanced.count_placed_words() + balanced_other.count_placed_words(), num_placed);
+            assert!(balanced.to_graph().is_connected());
+            assert!(balanced_other.to_graph().is_connected());
+
+            let naive_difference = (naive.count_placed_words() as isize - naive_other.count_placed_words() as isize).abs();
+            let balanced_difference = (balanced.count_placed_words() as isize - balanced_other.count_placed_words() as isize).abs();
+            assert!(balanced_difference <= naive_difference,
+                    "Seed {}: balanced difference {} worse than naive {}", seed, balanced_difference, naive_difference);
+        }
+    }
+
     fn count_successful_attempts(grid: &CrosswordGrid) -> usize {
         let mut num_successes = 0;
         for attempt in PlacementAttemptIterator::new(grid, 13) {
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use super::CrosswordGrid;
+use super::Direction;
+use super::Location;
+use super::Word;
+
+impl CrosswordGrid {
+    /// Transpose the grid, swapping rows and columns, so that across words become down words
+    /// and vice versa. Unlike a horizontal reflection, which would reverse the across answers,
+    /// this keeps every answer reading forwards. Clues are kept, and any required direction
+    /// is swapped to match.
+    pub fn transposed(&self) -> Self {
+        let mut words: BTreeMap<usize, Word> = BTreeMap::new();
+        for (word_id, word) in self.word_map.iter() {
+            let mut transposed_word = word.clone();
+            transposed_word.rotate_required_direction();
+            if let Some((start_location, _end_location, direction)) = word.get_location() {
+                transposed_word.update_location(Location(start_location.1, start_location.0), direction.rotate());
+            }
+            words.insert(*word_id, transposed_word);
+        }
+        CrosswordGrid::from_placed_words(words, self.meta.clone())
+            .expect("Transposing a valid grid should always give a valid grid")
+    }
+
+    /// Reflect the grid left to right. Across words stay across and down words stay down,
+    /// but the letters of each placed across word now read from right to left, so its
+    /// answer is reversed e.g. BEARER becomes RERAEB. Clues are kept unchanged, so readers
+    /// of the reflected grid need to know that the across answers are entered backwards.
+    pub fn reflected_horizontally(&self) -> Self {
+        let mut words: BTreeMap<usize, Word> = BTreeMap::new();
+        for (word_id, word) in self.word_map.iter() {
+            let mut reflected_word = word.clone();
+            match word.get_location() {
+                Some((start_location, end_location, Direction::Across)) => {
+                    reflected_word.word_text = word.word_text.chars().rev().collect();
+                    reflected_word.display_text = word.display_text.chars().rev().collect();
+                    reflected_word.update_location(Location(start_location.0, -end_location.1), Direction::Across);
+                },
+                Some((start_location, _end_location, Direction::Down)) => {
+                    reflected_word.update_location(Location(start_location.0, -start_location.1), Direction::Down);
+                },
+                None => {},
+            }
+            words.insert(*word_id, reflected_word);
+        }
+        CrosswordGrid::from_placed_words(words, self.meta.clone())
+            .expect("Reflecting a valid grid should always give a valid grid")
+    }
+
+    /// Ids of the placed across words, which are the answers reversed by reflected_horizontally
+    pub fn answers_reversed_by_reflection(&self) -> Vec<usize> {
+        let mut word_ids: Vec<usize> = self.word_map.iter()
+            .filter(|(_id, word)| word.get_location().is_some_and(|(_start, _end, direction)| direction == Direction::Across))
+            .map(|(id, _word)| *id)
+            .collect();
+        word_ids.sort();
+        word_ids
+    }
+
+    /// The grid and its horizontal reflection, as two independent grids e.g. for printing a
+    /// puzzle and its mirror on a two-page spread. Clues and directions are kept, but the
+    /// across answers of the mirror are reversed; these are the words listed by
+    /// answers_reversed_by_reflection. The original grid is not changed.
+    pub fn mirror_pair(&self) -> (CrosswordGrid, CrosswordGrid) {
+        (self.clone(), self.reflected_horizontally())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::CrosswordGridBuilder;
+    use super::super::Direction;
+
+    #[test]
+    fn test_transposed() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let before = grid.to_string();
+        let transposed = grid.transposed();
+        assert!(transposed.is_valid_crossword().is_ok());
+
+        let chars = grid.to_char_grid();
+        let transposed_chars = transposed.to_char_grid();
+        assert_eq!(transposed_chars.len(), chars[0].len());
+        for (row, line) in chars.iter().enumerate() {
+            for (col, c) in line.iter().enumerate() {
+                assert_eq!(transposed_chars[col][row], *c);
+            }
+        }
+
+        for (word_id, word) in grid.word_map.iter() {
+            let transposed_word = transposed.word_map.get(word_id).unwrap();
+            assert_eq!(transposed_word.clue, word.clue);
+            assert_eq!(transposed_word.get_location().map(|(_start, _end, direction)| direction.rotate()),
+                       word.get_location().map(|(_start, _end, direction)| direction));
+        }
+
+        // Transposing twice gives back the original grid
+        assert_eq!(transposed.transposed().to_string(), before);
+    }
+
+    #[test]
+    fn test_mirror_pair() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        grid.add_unplaced_word("UNPLACED", "Not in the grid (8)", Some(Direction::Across));
+        let before = grid.to_string();
+
+        let (first, second) = grid.mirror_pair();
+        assert_eq!(grid.to_string(), before);
+        assert_eq!(first.to_string(), before);
+        assert!(first.is_valid_crossword().is_ok());
+        assert!(second.is_valid_crossword().is_ok());
+
+        let first_chars = first.to_char_grid();
+        let second_chars = second.to_char_grid();
+        assert_eq!(second_chars.len(), first_chars.len());
+        for (first_line, second_line) in first_chars.iter().zip(second_chars.iter()) {
+            assert_eq!(second_line.iter().rev().collect::<Vec<&char>>(), first_line.iter().collect::<Vec<&char>>());
+        }
+
+        // Directions and clues are kept, but the across answers are reversed
+        let reversed = first.answers_reversed_by_reflection();
+        assert!(!reversed.is_empty());
+        for (word_id, word) in first.word_map.iter() {
+            let reflected_word = second.word_map.get(word_id).unwrap();
+            assert_eq!(reflected_word.clue, word.clue);
+            assert_eq!(reflected_word.get_location().map(|(_start, _end, direction)| direction),
+                       word.get_location().map(|(_start, _end, direction)| direction));
+            if reversed.contains(word_id) {
+                assert_eq!(reflected_word.word_text, word.word_text.chars().rev().collect::<String>());
+            } else {
+                assert_eq!(reflected_word.word_text, word.word_text);
+            }
+        }
+        assert!(second.all_answers().contains(&"RERAEB".to_string()));
+        assert_eq!(second.unplaced_answers(), vec!["UNPLACED"]);
+        assert_eq!(second.count_intersections(), first.count_intersections());
+
+        // Reflecting twice gives back the original grid
+        assert_eq!(second.reflected_horizontally().to_string(), before);
+        assert_eq!(second.reflected_horizontally().all_answers(), first.all_answers());
+    }
+}
@@ -1,4 +1,5 @@
 use log::debug;
+use std::cmp;
 
 use super::CrosswordGrid;
 use super::Location;
@@ -21,6 +22,24 @@ impl CrosswordGrid {
         }
     }
 
+    /// Grow the grid so that it contains every cell in the rectangle from top_left to
+    /// bottom_right, adding empty cells where needed. The grid is never shrunk.
+    pub fn expand_to_fit_region(&mut self, top_left: Location, bottom_right: Location) {
+        let new_top_left = Location(cmp::min(self.top_left_cell_index.0, cmp::min(top_left.0, bottom_right.0)),
+                                    cmp::min(self.top_left_cell_index.1, cmp::min(top_left.1, bottom_right.1)));
+        let new_bottom_right = Location(cmp::max(self.bottom_right_cell_index.0, cmp::max(top_left.0, bottom_right.0)),
+                                        cmp::max(self.bottom_right_cell_index.1, cmp::max(top_left.1, bottom_right.1)));
+        debug!("Expanding grid to fit region from {:?} to {:?}", new_top_left, new_bottom_right);
+
+        for row in new_top_left.0..=new_bottom_right.0 {
+            for col in new_top_left.1..=new_bottom_right.1 {
+                self.cell_map.entry(Location(row, col)).or_insert_with(Cell::empty);
+            }
+        }
+        self.top_left_cell_index = new_top_left;
+        self.bottom_right_cell_index = new_bottom_right;
+    }
+
     fn add_empty_row(&mut self, new_row: isize) {
         debug!("Adding new row at {}, top left is {:?}, bottom right is {:?}", new_row, self.top_left_cell_index, self.bottom_right_cell_index);
         let mut col = self.top_left_cell_index.1;
@@ -51,17 +70,17 @@ impl CrosswordGrid {
         }
     }
 
-    fn ensure_buffer_exists(&mut self) {
-        if self.count_filled_cells_row(self.top_left_cell_index.0) > 0 {
+    fn ensure_buffer_exists(&mut self, buffer: isize) {
+        while self.count_filled_cells_rows(self.top_left_cell_index.0, self.top_left_cell_index.0 + buffer - 1) > 0 {
             self.add_empty_row(self.top_left_cell_index.0 - 1);
         }
-        if self.count_filled_cells_row(self.bottom_right_cell_index.0) > 0 {
+        while self.count_filled_cells_rows(self.bottom_right_cell_index.0 - buffer + 1, self.bottom_right_cell_index.0) > 0 {
             self.add_empty_row(self.bottom_right_cell_index.0 + 1);
         }
-        if self.count_filled_cells_col(self.top_left_cell_index.1) > 0 {
+        while self.count_filled_cells_cols(self.top_left_cell_index.1, self.top_left_cell_index.1 + buffer - 1) > 0 {
             self.add_empty_col(self.top_left_cell_index.1 - 1);
         }
-        if self.count_filled_cells_col(self.bottom_right_cell_index.1) > 0 {
+        while self.count_filled_cells_cols(self.bottom_right_cell_index.1 - buffer + 1, self.bottom_right_cell_index.1) > 0 {
             self.add_empty_col(self.bottom_right_cell_index.1 + 1);
         }
     }
@@ -92,24 +111,37 @@ impl CrosswordGrid {
         }
     }
 
-    fn remove_excess_empty(&mut self) {
+    fn remove_excess_empty(&mut self, buffer: isize) {
+        // With no filled cells there is nothing to shrink towards
+        if self.count_filled_cells() == 0 {
+            return;
+        }
+
         // Remove excess rows
-        while self.count_filled_cells_row(self.top_left_cell_index.0 + 1) == 0 {
+        while self.count_filled_cells_rows(self.top_left_cell_index.0, self.top_left_cell_index.0 + buffer) == 0 {
             self.remove_row(self.top_left_cell_index.0)
         }
-        while self.count_filled_cells_row(self.bottom_right_cell_index.0 - 1) == 0 {
+        while self.count_filled_cells_rows(self.bottom_right_cell_index.0 - buffer, self.bottom_right_cell_index.0) == 0 {
             self.remove_row(self.bottom_right_cell_index.0)
         }
 
         // Remove excess columns
-        while self.count_filled_cells_col(self.top_left_cell_index.1 + 1) == 0 {
+        while self.count_filled_cells_cols(self.top_left_cell_index.1, self.top_left_cell_index.1 + buffer) == 0 {
             self.remove_col(self.top_left_cell_index.1)
         }
-        while self.count_filled_cells_col(self.bottom_right_cell_index.1 - 1) == 0 {
+        while self.count_filled_cells_cols(self.bottom_right_cell_index.1 - buffer, self.bottom_right_cell_index.1) == 0 {
             self.remove_col(self.bottom_right_cell_index.1)
         }
     }
 
+    fn count_filled_cells_rows(&self, first_row: isize, last_row: isize) -> usize {
+        (first_row..=last_row).map(|row| self.count_filled_cells_row(row)).sum()
+    }
+
+    fn count_filled_cells_cols(&self, first_col: isize, last_col: isize) -> usize {
+        (first_col..=last_col).map(|col| self.count_filled_cells_col(col)).sum()
+    }
+
     fn count_filled_cells_row(&self, row: isize) -> usize {
         let mut col = self.top_left_cell_index.1;
         let mut filled_count: usize = 0;
@@ -145,11 +177,24 @@ impl CrosswordGrid {
     /// Trim the grid so that there is exactly one row and column of empty
     /// cells on either side of the grid
     pub fn fit_to_size(&mut self) {
-        // First make sure we've got at least one buffer row and buffer column
-        self.ensure_buffer_exists();
+        self.fit_to_size_with_buffer(1);
+    }
+
+    /// Trim the grid so that there are exactly buffer rows and columns of empty
+    /// cells on either side of the grid. The buffer must be at least one cell, since
+    /// the black cells at the ends of words need to fit in the grid.
+    ///
+    /// Note that methods such as to_string assume a buffer of one cell, so will
+    /// include the extra margin.
+    pub fn fit_to_size_with_buffer(&mut self, buffer: usize) {
+        assert!(buffer >= 1, "Grid buffer must be at least one cell to leave room for black cells");
+        let buffer = buffer as isize;
+
+        // First make sure we've got enough buffer rows and buffer columns
+        self.ensure_buffer_exists(buffer);
 
         // Then check we don't have too many empty rows or columns
-        self.remove_excess_empty();
+        self.remove_excess_empty(buffer);
     }
 }
 
@@ -170,7 +215,7 @@ mod tests {
         }
         assert_eq!(grid.count_filled_cells_row(0), 5);
 
-        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
         let row_counts: Vec<usize> = vec![6, 2, 9, 3, 6, 3, 10, 2, 1];
         let col_counts: Vec<usize> = vec![2, 6, 5, 4, 4, 7, 3, 4, 5, 2];
 
@@ -182,6 +227,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_expand_to_fit_region() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.expand_to_fit_region(Location(-3, 2), Location(4, 9));
+        assert_eq!(grid.top_left_cell_index, Location(-3, -1));
+        assert_eq!(grid.bottom_right_cell_index, Location(4, 9));
+        assert_eq!(grid.cell_map.len(), 8*11);
+        for row in -3..=4 {
+            for col in -1..=9 {
+                assert!(grid.cell_map.contains_key(&Location(row, col)));
+            }
+        }
+        assert_eq!(grid.count_filled_cells(), 5);
+        grid.check_valid();
+
+        // A region already inside the grid changes nothing
+        grid.expand_to_fit_region(Location(0, 0), Location(1, 1));
+        assert_eq!(grid.cell_map.len(), 8*11);
+
+        grid.fit_to_size();
+        assert_eq!(grid.cell_map.len(), 7*3);
+    }
+
+    #[test]
+    fn test_fit_to_size_with_buffer() {
+        crate::logging::init_logger(true);
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.fit_to_size_with_buffer(2);
+        assert_eq!(grid.cell_map.len(), 9*5);
+        assert_eq!(grid.top_left_cell_index, Location(-2, -2));
+        assert_eq!(grid.bottom_right_cell_index, Location(2, 6));
+        grid.fill_black_cells();
+        assert!(grid.black_cells_valid());
+        grid.check_valid();
+
+        grid.fit_to_size_with_buffer(3);
+        assert_eq!(grid.cell_map.len(), 11*7);
+        grid.fit_to_size();
+        assert_eq!(grid.cell_map.len(), 7*3);
+
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        let (nrows, ncols) = grid.get_grid_dimensions_with_buffer();
+        grid.fit_to_size_with_buffer(2);
+        assert_eq!(grid.get_grid_dimensions_with_buffer(), (nrows + 2, ncols + 2));
+        grid.fill_black_cells();
+        assert!(grid.black_cells_valid());
+        grid.check_valid();
+    }
+
     #[test]
     fn test_fit_to_size() {
         crate::logging::init_logger(true);
@@ -192,7 +287,7 @@ mod tests {
         grid.fit_to_size();
         assert_eq!(grid.cell_map.len(), 7*3);
 
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
         // Number of non-empty cells shouldn't change
         grid.fit_to_size();
         let row_counts: Vec<usize> = vec![6, 2, 9, 3, 6, 3, 10, 2, 1];
@@ -205,7 +300,7 @@ mod tests {
             assert_eq!(grid.count_filled_cells_col(i as isize), col_counts[i]);
         }
 
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/blank_space.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/blank_space.txt").unwrap();
         // Number of non-empty cells shouldn't change
         grid.fit_to_size();
         info!("Grid coords {:#?} {:#?}", grid.top_left_cell_index, grid.bottom_right_cell_index);
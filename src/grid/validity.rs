@@ -5,6 +5,7 @@ use std::iter::FromIterator;
 use super::CrosswordGrid;
 use super::Location;
 use super::Direction;
+use super::Word;
 
 use super::CrosswordError;
 
@@ -47,6 +48,176 @@ impl CrosswordGrid {
         Ok(())
     }
 
+    /// Check that no placed word lies entirely within the cells of another placed word in the
+    /// same direction, including two words placed identically. This can't happen through
+    /// normal placement, but would make the solution ambiguous.
+    pub fn check_no_contained_words(&self) -> Result<(), CrosswordError> {
+        let mut placed_words: Vec<(usize, Location, Location, Direction)> = self.word_map.iter()
+            .filter_map(|(id, w)| w.get_location().map(|(start, end, direction)| (*id, start, end, direction)))
+            .collect();
+        placed_words.sort_by_key(|(id, _start, _end, _direction)| *id);
+
+        for (word_id, start, end, direction) in placed_words.iter() {
+            for (other_id, other_start, other_end, other_direction) in placed_words.iter() {
+                if word_id != other_id && direction == other_direction {
+                    let contained = match direction {
+                        Direction::Across => start.0 == other_start.0 && start.1 >= other_start.1 && end.1 <= other_end.1,
+                        Direction::Down => start.1 == other_start.1 && start.0 >= other_start.0 && end.0 <= other_end.0,
+                    };
+                    if contained {
+                        Err(CrosswordError::ContainedWord(*word_id, *other_id))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run all the structural checks on the grid, returning every violation found rather than
+    /// just the first. Unlike check_valid, this never panics, so is suitable for reporting
+    /// what is wrong with a grid. Every placed word must have at least 2 letters.
+    pub fn is_valid_crossword(&self) -> Result<(), Vec<CrosswordError>> {
+        let mut errors: Vec<CrosswordError> = vec![];
+        if !self.to_graph().is_connected() {
+            errors.push(CrosswordError::DisconnectedGrid);
+        }
+        if !self.black_cells_valid() {
+            errors.push(CrosswordError::InvalidBlackCells);
+        }
+        let checks = vec![self.check_word_letters_consistent(),
+                          self.check_all_word_placement_valid(),
+                          self.check_no_accidental_words(),
+                          self.check_no_self_crossing(),
+                          self.check_no_contained_words(),
+                          self.check_min_word_length(2)];
+        for check in checks {
+            if let Err(error) = check {
+                errors.push(error);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Check that the cells along each placed word contain the letters of the word
+    fn check_word_letters_consistent(&self) -> Result<(), CrosswordError> {
+        let mut placed_words: Vec<(&usize, &Word)> = self.word_map.iter().filter(|(_id, w)| w.is_placed()).collect();
+        placed_words.sort_by_key(|(id, _w)| **id);
+
+        for (word_id, word) in placed_words {
+            let (start_location, _end_location, direction) = word.get_location().unwrap();
+            let mut working_location = start_location;
+            for c in word.word_text.chars() {
+                if self.get_cell(&working_location)?.to_char() != c {
+                    Err(CrosswordError::WordLetterMismatch(*word_id, working_location))?;
+                }
+                working_location = working_location.relative_location_directed(1, direction);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every run of two or more letters along a row or column is exactly one
+    /// placed word. Otherwise e.g. two across words lying flush against each other would
+    /// form spurious down words, which aren't in the word list.
+    pub fn check_no_accidental_words(&self) -> Result<(), CrosswordError> {
+        for direction in [Direction::Across, Direction::Down].iter() {
+            let (num_lines, line_length) = match direction {
+                Direction::Across => (self.bottom_right_cell_index.0 - self.top_left_cell_index.0 + 1,
+                                      self.bottom_right_cell_index.1 - self.top_left_cell_index.1 + 1),
+                Direction::Down => (self.bottom_right_cell_index.1 - self.top_left_cell_index.1 + 1,
+                                    self.bottom_right_cell_index.0 - self.top_left_cell_index.0 + 1),
+            };
+            for line in 0..num_lines {
+                let line_start = match direction {
+                    Direction::Across => self.top_left_cell_index.relative_location(line, 0),
+                    Direction::Down => self.top_left_cell_index.relative_location(0, line),
+                };
+                let mut run: Vec<Location> = vec![];
+                // Go one cell past the end of the line so the final run is always checked
+                for position in 0..(line_length + 1) {
+                    let location = line_start.relative_location_directed(position, *direction);
+                    let contains_letter = position < line_length && self.get_cell(&location)?.contains_letter();
+                    if contains_letter {
+                        run.push(location);
+                    } else {
+                        if run.len() > 1 && !self.run_is_single_word(&run, *direction) {
+                            Err(CrosswordError::AccidentalWord(run[0], *direction))?;
+                        }
+                        run.clear();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn run_is_single_word(&self, run: &[Location], direction: Direction) -> bool {
+        let word_id = self.get_cell(&run[0]).unwrap().get_word_id(direction);
+        match word_id {
+            Some(word_id) => {
+                run.iter().all(|location| self.get_cell(location).unwrap().get_word_id(direction) == Some(word_id))
+                    && self.word_map.get(&word_id).map(|word| word.len()) == Some(run.len())
+            },
+            None => false,
+        }
+    }
+
+    /// Check that every placed word has at least min letters, returning an error for
+    /// the offending word with the lowest id if not.
+    pub fn check_min_word_length(&self, min: usize) -> Result<(), CrosswordError> {
+        let mut placed_words: Vec<(&usize, &Word)> = self.word_map.iter().filter(|(_id, w)| w.is_placed()).collect();
+        placed_words.sort_by_key(|(id, _w)| **id);
+
+        for (word_id, word) in placed_words {
+            if word.len() < min {
+                Err(CrosswordError::WordTooShort(*word_id, word.len()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the cells belonging to each placed word are distinct and lie in a straight
+    /// line from its start, matching its length and direction. This can't happen through normal
+    /// placement, but a bug could leave cells claiming a word which crosses itself.
+    pub fn check_no_self_crossing(&self) -> Result<(), CrosswordError> {
+        let mut placed_words: Vec<(&usize, &Word)> = self.word_map.iter().filter(|(_id, w)| w.is_placed()).collect();
+        placed_words.sort_by_key(|(id, _w)| **id);
+
+        for (word_id, word) in placed_words {
+            let (start_location, end_location, direction) = word.get_location().unwrap();
+            let mut expected_locations: HashSet<Location> = HashSet::new();
+            let mut working_location = start_location;
+            for _i in 0..word.len() {
+                expected_locations.insert(working_location);
+                working_location = working_location.relative_location_directed(1, direction);
+            }
+            if working_location.relative_location_directed(-1, direction) != end_location {
+                Err(CrosswordError::SelfCrossingWord(*word_id))?;
+            }
+
+            let mut num_cells = 0;
+            for (location, cell) in self.cell_map.iter() {
+                for cell_direction in [Direction::Across, Direction::Down].iter() {
+                    if cell.get_word_id(*cell_direction) == Some(*word_id) {
+                        num_cells += 1;
+                        if *cell_direction != direction || !expected_locations.contains(location) {
+                            Err(CrosswordError::SelfCrossingWord(*word_id))?;
+                        }
+                    }
+                }
+            }
+            if num_cells != word.len() {
+                Err(CrosswordError::SelfCrossingWord(*word_id))?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn black_cells_valid(&self) -> bool {
         let black_cells_set: HashSet<Location> = HashSet::from_iter(self.get_expected_black_cells().iter().cloned());
         let mut valid = true;
@@ -58,7 +229,7 @@ impl CrosswordGrid {
         }
 
         for location in black_cells_set {
-            if !self.cell_map.get(&location).unwrap().is_black() {
+            if !self.cell_map.get(&location).is_some_and(|cell| cell.is_black()) {
                 valid = false;
             }
         }
@@ -164,9 +335,88 @@ mod tests {
     use super::super::CrosswordGridBuilder;
     use super::super::CellError;
 
+    #[test]
+    fn test_check_no_contained_words() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.check_no_contained_words(), Ok(()));
+
+        // Simulate a buggy merge placing LPH in the middle of ALPHA
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let lph_word_id = grid.add_unplaced_word("LPH", "", None);
+        grid.word_map.get_mut(&lph_word_id).unwrap().update_location(Location(0, 1), Direction::Across);
+        assert_eq!(grid.check_no_contained_words(), Err(CrosswordError::ContainedWord(lph_word_id, 0)));
+
+        // A word running in the other direction through ALPHA isn't contained in it
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let lph_word_id = grid.add_unplaced_word("LPH", "", None);
+        grid.word_map.get_mut(&lph_word_id).unwrap().update_location(Location(0, 1), Direction::Down);
+        assert_eq!(grid.check_no_contained_words(), Ok(()));
+
+        // Identical placements are contained in each other
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        let copy_word_id = grid.add_unplaced_word("ALPHA", "", None);
+        grid.word_map.get_mut(&copy_word_id).unwrap().update_location(Location(0, 0), Direction::Across);
+        assert_eq!(grid.check_no_contained_words(), Err(CrosswordError::ContainedWord(0, copy_word_id)));
+    }
+
+    #[test]
+    fn test_is_valid_crossword() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.is_valid_crossword(), Ok(()));
+
+        // Changing a letter of ALPHA leaves the grid inconsistent, and a placed word away from
+        // ALPHA with no cells disconnects the grid and has no black cells around it
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.cell_map.get_mut(&Location(0, 2)).unwrap().set_empty();
+        grid.cell_map.get_mut(&Location(0, 2)).unwrap().add_word(0, 'Q', Direction::Across).unwrap();
+        let far_word_id = grid.add_unplaced_word("BETA", "", None);
+        grid.word_map.get_mut(&far_word_id).unwrap().update_location(Location(10, 10), Direction::Across);
+
+        let errors = grid.is_valid_crossword().unwrap_err();
+        assert!(errors.len() >= 3, "Expected several errors, found {:?}", errors);
+        assert!(errors.contains(&CrosswordError::DisconnectedGrid));
+        assert!(errors.contains(&CrosswordError::InvalidBlackCells));
+        assert!(errors.contains(&CrosswordError::WordLetterMismatch(0, Location(0, 2))));
+    }
+
+    #[test]
+    fn test_check_min_word_length() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.check_min_word_length(3), Ok(()));
+        assert_matches!(grid.check_min_word_length(4), Err(CrosswordError::WordTooShort(_, 3)));
+
+        let grid = CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+        assert_eq!(grid.check_min_word_length(3), Ok(()));
+
+        let grid = CrosswordGridBuilder::new().from_string("ALE\nT  \n");
+        let at_word_id = grid.get_cell(&Location(0, 0)).unwrap().get_down_word_id().unwrap();
+        assert_eq!(grid.check_min_word_length(3), Err(CrosswordError::WordTooShort(at_word_id, 2)));
+        assert_eq!(grid.check_min_word_length(2), Ok(()));
+    }
+
+    #[test]
+    fn test_check_no_self_crossing() {
+        crate::logging::init_logger(true);
+        let grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+        assert_eq!(grid.check_no_self_crossing(), Ok(()));
+
+        // Corrupt the middle cell of ALPHA so that the word also runs down through it
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.cell_map.get_mut(&Location(0, 2)).unwrap().add_word(0, 'P', Direction::Down).unwrap();
+        assert_eq!(grid.check_no_self_crossing(), Err(CrosswordError::SelfCrossingWord(0)));
+
+        // A cell off the line of the word claims to belong to it
+        let mut grid = CrosswordGrid::new_single_word("ALPHA");
+        grid.cell_map.get_mut(&Location(1, 1)).unwrap().add_word(0, 'X', Direction::Across).unwrap();
+        assert_eq!(grid.check_no_self_crossing(), Err(CrosswordError::SelfCrossingWord(0)));
+    }
+
     #[test]
     fn test_valid_word_placement() -> Result<(), CrosswordError> {
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt").unwrap();
         let bat_id = grid.add_unplaced_word("BAT", "", None);
         println!("{}", grid.to_string_with_coords());
         let result: Result<(), CrosswordError> = Err(CrosswordError::CellError(Location(2, 3),
@@ -189,7 +439,7 @@ mod tests {
                                                                             bat_id,
                                                                             _)));
 
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt").unwrap();
         let bet_id = grid.add_unplaced_word("BET", "", None);
         grid.no_check_place_word_in_cell(Location(0, 6),
                                          bet_id,
@@ -216,7 +466,7 @@ mod tests {
         assert!(grid.cell_map.get(&Location(0, -1)).unwrap().is_black());
         assert!(grid.cell_map.get(&Location(0, 5)).unwrap().is_black());
 
-        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+        let mut grid = CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
         grid.fit_to_size();
         grid.fill_black_cells();
         assert_eq!(grid.cell_map.values().filter(|&x| x.is_black()).count(), 18);
@@ -1,4 +1,5 @@
 use log::warn;
+use serde::{Deserialize,Serialize};
 use thiserror::Error;
 
 use super::Location;
@@ -18,7 +19,16 @@ pub enum ParseError {
     EmptyAnswer(String)
 }
 
-fn parse_answer_string(string: &str) -> Result<(String, String), ParseError> {
+/// How the word lengths are written in a clue, e.g. for the answer "BELLY FLOP".
+#[derive(Clone,Copy,Debug,Eq,PartialEq,Deserialize,Serialize)]
+pub enum EnumerationStyle {
+    /// Length of each word in the answer e.g. "(5,4)", or "(3-4)" for hyphenated answers
+    Uk,
+    /// Total length of the answer only e.g. "(9)"
+    Us,
+}
+
+pub(crate) fn parse_answer_string(string: &str, enumeration_style: EnumerationStyle) -> Result<(String, String), ParseError> {
     let mut word_lengths = String::from("(");
     let mut word = String::new();
     let mut current_word_len = 0;
@@ -32,6 +42,8 @@ fn parse_answer_string(string: &str) -> Result<(String, String), ParseError> {
                 word_lengths.push_str(&format!("{},", current_word_len));
                 current_word_len = 0;
             },
+            // Apostrophes are shown in the answer but aren't part of the grid
+            '\'' => {},
             'A'..='z' => {
                 word.push(c.to_ascii_uppercase());
                 current_word_len += 1;
@@ -40,6 +52,9 @@ fn parse_answer_string(string: &str) -> Result<(String, String), ParseError> {
         }
     }
     word_lengths.push_str(&format!("{})", current_word_len));
+    if enumeration_style == EnumerationStyle::Us {
+        word_lengths = format!("({})", word.len());
+    }
 
     match word.len() {
         0 => Err(ParseError::EmptyAnswer(string.to_string())),
@@ -47,18 +62,31 @@ fn parse_answer_string(string: &str) -> Result<(String, String), ParseError> {
     }
 }
 
-fn clue_contains_word_lengths(string: &str) -> bool {
+/// The answer as it should be displayed, keeping any spaces, hyphens and apostrophes
+fn answer_display_text(string: &str) -> String {
+    string.trim().to_ascii_uppercase()
+}
+
+/// The word lengths given in the clue, e.g. "(5,4)", or None if there are none
+pub(crate) fn clue_word_lengths(string: &str) -> Option<&str> {
     lazy_static! {
         static ref RE: Regex = Regex::new(r"\([-,\d]+\)").unwrap();
     }
-    RE.is_match(string)
+    RE.find(string).map(|m| m.as_str())
 }
 
-fn parse_clue_string(string: &str) -> Result<(String, String, Option<Direction>), ParseError> {
+fn clue_contains_word_lengths(string: &str) -> bool {
+    clue_word_lengths(string).is_some()
+}
+
+/// Parse a line of a clue file, in the format ANSWER::clue::DIRECTION::DIFFICULTY where
+/// everything after the answer is optional. Returns the sanitised answer, the clue
+/// including word lengths, the required direction and the difficulty.
+fn parse_clue_string(string: &str, enumeration_style: EnumerationStyle) -> Result<(String, String, Option<Direction>, Option<u8>), ParseError> {
     let mut components = string.split("::");
 
     let word_text: &str = components.next().unwrap();
-    let (sanitised_word, word_lengths) = parse_answer_string(word_text)?;
+    let (sanitised_word, word_lengths) = parse_answer_string(word_text, enumeration_style)?;
     let clue: &str = match components.next() {
         Some(clue_text) => clue_text,
         None => "",
@@ -78,10 +106,34 @@ fn parse_clue_string(string: &str) -> Result<(String, String, Option<Direction>)
         },
         None => None,
     };
-    Ok((sanitised_word, sanitised_clue, required_direction))
+
+    let difficulty: Option<u8> = match components.next().map(|x| x.trim()) {
+        Some("") => None,
+        Some(x) => match x.parse::<u8>() {
+            Ok(difficulty) => Some(difficulty),
+            Err(_) => {
+                warn!("Unexpected difficulty at end of clue, expected a number from 0 to 255. Parsed as if it were empty. {}", x);
+                None
+            },
+        },
+        None => None,
+    };
+    Ok((sanitised_word, sanitised_clue, required_direction, difficulty))
 }
 
-#[derive(Clone,Copy,Debug)]
+/// Parse a line in the clue file format and return just the sanitised answer text,
+/// or None if the line cannot be parsed.
+pub(crate) fn parse_answer_text(string: &str) -> Option<String> {
+    match Word::new_parsed(string) {
+        Ok(word) => Some(word.word_text),
+        Err(error) => {
+            warn!("Skipping answer which couldn't be parsed: {}", error);
+            None
+        },
+    }
+}
+
+#[derive(Clone,Copy,Debug,Deserialize,Serialize)]
 struct WordPlacement {
     start_location: Location,
     end_location: Location,
@@ -103,36 +155,54 @@ impl WordPlacement {
     }
 }
 
-#[derive(Clone,Debug)]
-pub(super) struct Word {
+#[derive(Clone,Debug,Deserialize,Serialize)]
+pub(crate) struct Word {
     pub word_text: String,
+    // Answer including punctuation which doesn't appear in the grid e.g. O'CLOCK
+    pub display_text: String,
     placement: Option<WordPlacement>,
     pub clue: String,
     required_direction: Option<Direction>,
+    // How hard the clue is, on a scale chosen by the setter, if given in the clue file
+    #[serde(default)]
+    pub difficulty: Option<u8>,
 }
 
 impl Word {
     pub fn new(string: &str, start_location: Location, direction: Direction, required_direction: Option<Direction>) -> Self {
         Word {
             word_text: string.to_string(),
+            display_text: string.to_string(),
             placement: Some(WordPlacement::new(string, start_location, direction)),
             clue: "Bla bla bla (6)".to_string(),
             required_direction,
+            difficulty: None,
         }
     }
 
     pub fn new_unplaced(word_text: &str, clue: &str, required_direction: Option<Direction>) -> Self {
         Word {
             word_text: word_text.to_string(),
+            display_text: word_text.to_string(),
             placement: None,
             clue: clue.to_string(),
             required_direction,
+            difficulty: None,
         }
     }
 
     pub fn new_parsed(string: &str) -> Result<Self, ParseError> {
-        let (word, clue, required_direction) = parse_clue_string(string)?;
-        Ok(Word::new_unplaced(&word, &clue, required_direction))
+        Word::new_parsed_with_style(string, EnumerationStyle::Uk)
+    }
+
+    /// Parse the word from a line of a clue file, adding word lengths to the clue
+    /// in the given style if the clue doesn't already include them.
+    pub fn new_parsed_with_style(string: &str, enumeration_style: EnumerationStyle) -> Result<Self, ParseError> {
+        let (word, clue, required_direction, difficulty) = parse_clue_string(string, enumeration_style)?;
+        let mut parsed = Word::new_unplaced(&word, &clue, required_direction);
+        parsed.difficulty = difficulty;
+        parsed.display_text = answer_display_text(string.split("::").next().unwrap());
+        Ok(parsed)
     }
 
     pub fn get_location(&self) -> Option<(Location, Location, Direction)> {
@@ -149,6 +219,7 @@ impl Word {
 
     pub fn extend_word(&mut self, character: char) -> Option<Location> {
         self.word_text.push(character);
+        self.display_text.push(character);
         if let Some(word_placement) = &self.placement {
             let mut new_word_placement = word_placement.clone();
             new_word_placement.end_location = word_placement.end_location.relative_location_directed(1, word_placement.direction);
@@ -175,6 +246,11 @@ impl Word {
         self.required_direction
     }
 
+    /// Swap any required direction, e.g. when the grid is transposed so across words become down
+    pub fn rotate_required_direction(&mut self) {
+        self.required_direction = self.required_direction.map(|direction| direction.rotate());
+    }
+
     pub fn allowed_in_direction(&self, direction: Direction) -> bool {
         match self.required_direction {
             // If no requirements, anything is allowed
@@ -218,8 +294,27 @@ mod tests {
            "ANOXIC", "Gripped by sudden fear, topless opponents in game lacking vital element (6)", None),
       )]
     fn test_parse_clue_string(clue_string: &str, word: &str, clue: &str, required_direction: Option<Direction>) -> Result<(), ParseError> {
-        assert_eq!(parse_clue_string(clue_string)?,
-                   (word.to_string(), clue.to_string(), required_direction));
+        assert_eq!(parse_clue_string(clue_string, EnumerationStyle::Uk)?,
+                   (word.to_string(), clue.to_string(), required_direction, None));
+        Ok(())
+    }
+
+    #[rstest(clue_string, required_direction, difficulty,
+      case("WORD::clue::ACROSS::3", Some(Direction::Across), Some(3)),
+      case("WORD::clue::DOWN:: 10 ", Some(Direction::Down), Some(10)),
+      case("WORD::clue::::1", None, Some(1)),
+      case("WORD::clue::ACROSS::", Some(Direction::Across), None),
+      case("WORD::clue::ACROSS::hard", Some(Direction::Across), None),
+      case("WORD::clue::ACROSS::300", Some(Direction::Across), None),
+      case("WORD::clue::ACROSS", Some(Direction::Across), None),
+      case("WORD::clue", None, None),
+      )]
+    fn test_parse_clue_difficulty(clue_string: &str, required_direction: Option<Direction>, difficulty: Option<u8>) -> Result<(), ParseError> {
+        crate::logging::init_logger(true);
+        assert_eq!(parse_clue_string(clue_string, EnumerationStyle::Uk)?,
+                   ("WORD".to_string(), "clue (4)".to_string(), required_direction, difficulty));
+        let word = Word::new_parsed(clue_string)?;
+        assert_eq!(word.difficulty, difficulty);
         Ok(())
     }
 
@@ -228,14 +323,57 @@ mod tests {
       case("BILBO BAGGINS", "BILBOBAGGINS", "(5,7)"),
       case("tea-time", "TEATIME", "(3-4)"),
       case("tea-TIME", "TEATIME", "(3-4)"),
+      case("O'CLOCK", "OCLOCK", "(6)"),
+      case("DON'T", "DONT", "(4)"),
+      case("rock 'n' roll", "ROCKNROLL", "(4,1,4)"),
       )]
     fn test_parse_answer_string(string: &str, word: &str, word_lengths: &str) -> Result<(), ParseError> {
         crate::logging::init_logger(true);
-        assert_eq!(parse_answer_string(string)?,
+        assert_eq!(parse_answer_string(string, EnumerationStyle::Uk)?,
+                   (word.to_string(), word_lengths.to_string()));
+        Ok(())
+    }
+
+    #[rstest(string, enumeration_style, word, word_lengths,
+      case("BELLY FLOP", EnumerationStyle::Uk, "BELLYFLOP", "(5,4)"),
+      case("BELLY FLOP", EnumerationStyle::Us, "BELLYFLOP", "(9)"),
+      case("TEA-TIME", EnumerationStyle::Us, "TEATIME", "(7)"),
+      case("WORD", EnumerationStyle::Us, "WORD", "(4)"),
+      )]
+    fn test_parse_answer_string_style(string: &str, enumeration_style: EnumerationStyle, word: &str, word_lengths: &str) -> Result<(), ParseError> {
+        crate::logging::init_logger(true);
+        assert_eq!(parse_answer_string(string, enumeration_style)?,
                    (word.to_string(), word_lengths.to_string()));
         Ok(())
     }
 
+    #[rstest(clue_string, word_text, display_text,
+      case("O'CLOCK::Time", "OCLOCK", "O'CLOCK"),
+      case("o'clock", "OCLOCK", "O'CLOCK"),
+      case("BELLY FLOP::Bad dive", "BELLYFLOP", "BELLY FLOP"),
+      case("TEATIME", "TEATIME", "TEATIME"),
+      )]
+    fn test_display_text(clue_string: &str, word_text: &str, display_text: &str) -> Result<(), ParseError> {
+        crate::logging::init_logger(true);
+        let word = Word::new_parsed(clue_string)?;
+        assert_eq!(word.word_text, word_text);
+        assert_eq!(word.display_text, display_text);
+        Ok(())
+    }
+
+    #[rstest(clue_string, enumeration_style, clue,
+      case("BELLY FLOP::Bad dive", EnumerationStyle::Uk, "Bad dive (5,4)"),
+      case("BELLY FLOP::Bad dive", EnumerationStyle::Us, "Bad dive (9)"),
+      case("BELLY FLOP::Bad dive (5,4)", EnumerationStyle::Us, "Bad dive (5,4)"),
+      )]
+    fn test_new_parsed_with_style(clue_string: &str, enumeration_style: EnumerationStyle, clue: &str) -> Result<(), ParseError> {
+        crate::logging::init_logger(true);
+        let word = Word::new_parsed_with_style(clue_string, enumeration_style)?;
+        assert_eq!(word.word_text, "BELLYFLOP");
+        assert_eq!(word.clue, clue);
+        Ok(())
+    }
+
     #[rstest(input, expected,
       case("Lines up outside No 10 — speech just beginning (6)", true),
 	  case("Lines up outside No 10 — speech just beginning (3-4)", true),
@@ -1,5 +1,5 @@
 use std::hash::Hash;
-use std::collections::HashMap;
+use std::collections::{HashMap,HashSet};
 
 #[macro_use]
 extern crate ndarray;
@@ -15,12 +15,92 @@ pub mod grid;
 pub mod logging;
 pub mod generator;
 pub mod utils;
+mod lint;
+
+pub use lint::{lint_clue_file,lint_clue_contents,ClueLint,ClueLintKind,DEFAULT_MIN_ANSWER_LENGTH};
 
 pub fn sanitise_string(string: &str, allowed_chars: &str) -> String {
     let sanitised = string.replace(|c: char| allowed_chars.find(c).is_none(), "");
     sanitised
 }
 
+/// Find the answers shared by two lists of clues, each given in the clue file format.
+/// Answers are compared after parsing and sanitising, so "belly-flop" and "BELLY FLOP"
+/// count as the same answer. Returns the shared answers sorted alphabetically.
+pub fn answer_overlap(list_a: &[&str], list_b: &[&str]) -> Vec<String> {
+    let answers_a: HashSet<String> = list_a.iter().filter_map(|line| grid::parse_answer_text(line)).collect();
+    let answers_b: HashSet<String> = list_b.iter().filter_map(|line| grid::parse_answer_text(line)).collect();
+
+    let mut overlap: Vec<String> = answers_a.intersection(&answers_b).cloned().collect();
+    overlap.sort();
+    overlap
+}
+
+/// A loose upper bound on the number of intersections in any grid built from these words:
+/// the number of letters shared by each pair of words, summed over all pairs. Repeated
+/// letters are only counted as shared as many times as they appear in both words.
+pub fn max_possible_intersections(words: &[&str]) -> usize {
+    let letter_counts: Vec<HashMap<char, usize>> = words.iter()
+        .filter_map(|line| grid::parse_answer_text(line))
+        .map(|word| {
+            let mut counts: HashMap<char, usize> = HashMap::new();
+            for c in word.chars() {
+                *counts.entry(c).or_insert(0) += 1;
+            }
+            counts
+        })
+        .collect();
+
+    let mut total = 0;
+    for (i, counts) in letter_counts.iter().enumerate() {
+        for other_counts in letter_counts.iter().skip(i + 1) {
+            for (letter, count) in counts.iter() {
+                total += std::cmp::min(*count, *other_counts.get(letter).unwrap_or(&0));
+            }
+        }
+    }
+    total
+}
+
+/// Find the answers in the list which fit a slot pattern, where each entry of the pattern
+/// is either a fixed letter or None for an unknown letter. Answers are sanitised and
+/// uppercased before matching, and are returned in that form in the order given.
+pub fn words_matching_pattern(words: &[&str], pattern: &[Option<char>]) -> Vec<String> {
+    let pattern: Vec<Option<char>> = pattern.iter().map(|c| c.map(|c| c.to_ascii_uppercase())).collect();
+    words.iter()
+        .filter_map(|line| grid::parse_answer_text(line))
+        .filter(|word| {
+            word.chars().count() == pattern.len()
+                && word.chars().zip(pattern.iter()).all(|(c, p)| p.is_none() || *p == Some(c))
+        })
+        .collect()
+}
+
+/// Split the answers into groups which could possibly be connected in a single grid, being
+/// the connected components of the graph linking each pair of answers sharing a letter.
+/// Answers are sanitised and uppercased, and groups are ordered by their first answer.
+pub fn partition_by_shared_letters(words: &[&str]) -> Vec<Vec<String>> {
+    let answers: Vec<String> = words.iter().filter_map(|line| grid::parse_answer_text(line)).collect();
+    let letter_sets: Vec<HashSet<char>> = answers.iter().map(|answer| answer.chars().collect()).collect();
+
+    let mut edges: Vec<(usize, usize)> = vec![];
+    for (i, letters) in letter_sets.iter().enumerate() {
+        for (j, other_letters) in letter_sets.iter().enumerate().skip(i + 1) {
+            if !letters.is_disjoint(other_letters) {
+                edges.push((i, j));
+            }
+        }
+    }
+    let mut graph = graph::Graph::new_from_edges(edges);
+    for i in 0..answers.len() {
+        graph.add_node(i);
+    }
+
+    graph.get_connected_components().iter()
+        .map(|component| component.iter().map(|i| answers[*i].clone()).collect())
+        .collect()
+}
+
 pub fn custom_hashmap_format<U, T>(hashmap: &HashMap<U, T>,
                                    key_prefix: &str,
                                    delimiter: &str) -> String
@@ -28,10 +108,16 @@ where
     U: std::fmt::Debug + Eq + Hash,
     T: std::fmt::Debug,
 {
+    // Sort the entries so the output doesn't depend on the arbitrary order of the hashmap
+    let mut entries: Vec<(String, String)> = hashmap.iter()
+        .map(|(key, value)| (format!("{:#?}", key), format!("{:#?}", value)))
+        .collect();
+    entries.sort();
+
     let mut result = String::new();
     result.push_str("(( ");
-    for (key, value) in hashmap.iter() {
-        result.push_str(&format!("{}{:#?}{}{:#?}, ",
+    for (key, value) in entries.iter() {
+        result.push_str(&format!("{}{}{}{}, ",
                                 key_prefix,
                                 key,
                                 delimiter,
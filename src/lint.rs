@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use thiserror::Error;
+
+use crate::grid::{EnumerationStyle,ParseError};
+use crate::grid::{parse_answer_string,clue_word_lengths};
+
+/// Answers shorter than this are reported by lint_clue_file
+pub const DEFAULT_MIN_ANSWER_LENGTH: usize = 3;
+
+#[derive(Error,Debug,PartialEq)]
+pub enum ClueLintKind {
+    #[error("Invalid character '{0}' in answer")]
+    InvalidAnswerChar(char),
+
+    #[error("Answer is empty")]
+    EmptyAnswer,
+
+    #[error("Answer {0} already appears on line {1}")]
+    DuplicateAnswer(String, usize),
+
+    #[error("Answer {0} is shorter than {1} letters")]
+    ShortAnswer(String, usize),
+
+    #[error("Clue gives word lengths {0} but the answer has word lengths {1}")]
+    EnumerationMismatch(String, String),
+}
+
+/// A problem found in a line of a clue file, with line numbers starting from 1
+#[derive(Debug,PartialEq)]
+pub struct ClueLint {
+    pub line_number: usize,
+    pub kind: ClueLintKind,
+}
+
+/// Check every line of a clue file for problems, without generating any grids.
+/// Returns an error if the file can't be read.
+pub fn lint_clue_file(path: &str) -> Result<Vec<ClueLint>, io::Error> {
+    let contents = fs::read_to_string(path)?;
+    Ok(lint_clue_contents(&contents, DEFAULT_MIN_ANSWER_LENGTH))
+}
+
+/// Check each line in the clue file format for problems, ordered by line number. Blank lines
+/// and header lines starting with '#' are skipped. The word lengths given in a clue can be
+/// in either the UK or the US style.
+pub fn lint_clue_contents(contents: &str, min_answer_length: usize) -> Vec<ClueLint> {
+    let mut lints: Vec<ClueLint> = vec![];
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut components = line.split("::");
+        let answer_text = components.next().unwrap();
+        let clue = components.next().unwrap_or("");
+
+        let (answer, uk_lengths) = match parse_answer_string(answer_text, EnumerationStyle::Uk) {
+            Ok(parsed) => parsed,
+            Err(ParseError::InvalidAnswerChar(c, _)) => {
+                lints.push(ClueLint { line_number, kind: ClueLintKind::InvalidAnswerChar(c) });
+                continue;
+            },
+            Err(ParseError::EmptyAnswer(_)) => {
+                lints.push(ClueLint { line_number, kind: ClueLintKind::EmptyAnswer });
+                continue;
+            },
+        };
+
+        match first_seen.get(&answer) {
+            Some(first_line_number) => {
+                lints.push(ClueLint { line_number, kind: ClueLintKind::DuplicateAnswer(answer.clone(), *first_line_number) });
+            },
+            None => {
+                first_seen.insert(answer.clone(), line_number);
+            },
+        }
+
+        if answer.len() < min_answer_length {
+            lints.push(ClueLint { line_number, kind: ClueLintKind::ShortAnswer(answer.clone(), min_answer_length) });
+        }
+
+        if let Some(clue_lengths) = clue_word_lengths(clue) {
+            let us_lengths = format!("({})", answer.len());
+            if clue_lengths != uk_lengths && clue_lengths != us_lengths {
+                lints.push(ClueLint { line_number, kind: ClueLintKind::EnumerationMismatch(clue_lengths.to_string(), uk_lengths) });
+            }
+        }
+    }
+    lints
+}
@@ -12,7 +12,13 @@ fn main() {
 
     let setting_names = ["num-per-gen", "num-children", "max-rounds", "seed", "moves-between-scores", "num-partitions",
                          "weight-non-square", "weight-prop-filled", "weight-prop-intersect", "weight-num-cycles",
-                         "weight-num-intersect", "weight-avg-intersect", "weight-words-placed"];
+                         "weight-num-intersect", "weight-avg-intersect", "weight-words-placed", "weight-compactness",
+                         "us-enumeration", "diversity-metric", "hash-dedup", "diversity-weight",
+                         "normalize-scores", "require-min-cycles",
+                         "weight-black-fraction", "target-black-fraction", "forbid-solid-squares",
+                         "weight-edge-words", "on-empty", "weight-intersection-centrality",
+                         "prefer-long-words", "weight-spine-penalty",
+                         "record-move-log", "keep-best-ever"];
     let mut setting_args: Vec<Arg> = vec![];
     for setting_name in setting_names.iter() {
         setting_args.push(Arg::with_name(&setting_name).long(&setting_name).takes_value(true));
@@ -26,6 +32,10 @@ fn main() {
                 .long("input-file")
                 .takes_value(true)
                 .required(true))
+        .arg(Arg::with_name("seed-text")
+                .long("seed-text")
+                .takes_value(true)
+                .conflicts_with("seed"))
         .args(&setting_args)
         .get_matches();
 
@@ -38,11 +48,28 @@ fn main() {
             Err(error) => { panic!("Failed to parse arguments - invalid argument given. {}", error); },
         }
     }
+    if let Some(seed_text) = matches.value_of("seed-text") {
+        let seed = crossword::generator::CrosswordGeneratorSettings::seed_from_str(seed_text);
+        settings_map.insert("seed", seed as usize);
+    }
     println!("{:?}", settings_map);
 
-    let mut generator = crossword::generator::CrosswordGenerator::new_from_file(matches.value_of("CLUE_FILE").unwrap(), settings_map);
+    let clue_file = matches.value_of("CLUE_FILE").unwrap();
+    let mut generator = match crossword::generator::CrosswordGenerator::new_from_file(clue_file, settings_map) {
+        Ok(generator) => generator,
+        Err(error) => {
+            eprintln!("Unable to read clue file {}: {}", clue_file, error);
+            std::process::exit(1);
+        },
+    };
 
-    let results = generator.generate();
+    let results = match generator.generate() {
+        Ok(results) => results,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        },
+    };
     for grid in results.iter() {
         println!("{}", grid.to_string());
     }
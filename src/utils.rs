@@ -68,3 +68,35 @@ impl<T: Eq + Hash> Counter<T> {
         self.counts
     }
 }
+
+impl<T: Eq + Hash + Ord> Counter<T> {
+    /// The k keys with the highest counts, sorted by descending count, with ties broken
+    /// by ascending key.
+    ///
+    /// ```
+    /// use crossword::utils::Counter;
+    /// let mut counter: Counter<char> = Counter::new();
+    /// for c in "BANANA".chars() {
+    ///     counter.increment(c);
+    /// }
+    /// assert_eq!(counter.most_common(2), vec![(&'A', 3), (&'N', 2)]);
+    /// assert_eq!(counter.most_common(10), vec![(&'A', 3), (&'N', 2), (&'B', 1)]);
+    /// ```
+    pub fn most_common(&self, k: usize) -> Vec<(&T, usize)> {
+        let mut entries: Vec<(&T, usize)> = self.counts.iter().map(|(key, count)| (key, *count)).collect();
+        entries.sort_by(|(key1, count1), (key2, count2)| count2.cmp(count1).then(key1.cmp(key2)));
+        entries.truncate(k);
+        entries
+    }
+}
+
+/// 64-bit FNV-1a hash of the text. Unlike the standard library's hasher, this is
+/// guaranteed to give the same value across runs, platforms and compiler versions.
+pub fn fnv1a_64(s: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in s.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -8,7 +8,7 @@ fn assert_approx_equal(a: f64, b: f64) -> bool {
 
 #[test]
 fn read_from_file() {
-    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
     println!("{:#?}", grid);
     assert_eq!(grid.count_all_words(), 10);
     assert_eq!(grid.count_intersections(), 11);
@@ -19,10 +19,542 @@ fn read_from_file() {
     grid.check_valid();
 }
 
+#[test]
+fn read_from_missing_file() {
+    let result = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/no_such_file.txt");
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+
+    let result = crossword::generator::CrosswordGenerator::new_from_file("tests/resources/no_such_file.txt", HashMap::new());
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+
+    let result = crossword::generator::CrosswordGenerator::new_from_dictionary("tests/resources/no_such_file.txt", 10, HashMap::new());
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn hashmap_format_is_stable() {
+    let keys = ["seed", "num-children", "max-rounds", "weight-non-square", "num-partitions", "min-rounds"];
+    let mut forwards: HashMap<&str, usize> = HashMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        forwards.insert(key, i);
+    }
+    let mut backwards: HashMap<&str, usize> = HashMap::new();
+    for (i, key) in keys.iter().enumerate().rev() {
+        backwards.insert(key, i);
+    }
+
+    let formatted = crossword::custom_hashmap_format(&forwards, "", ": ");
+    assert_eq!(formatted, crossword::custom_hashmap_format(&backwards, "", ": "));
+    assert_eq!(formatted, crossword::custom_hashmap_format(&forwards.clone(), "", ": "));
+    assert!(formatted.starts_with("(( \"max-rounds\": 2, \"min-rounds\": 5, "));
+}
+
+#[test]
+fn max_possible_intersections() {
+    // APPLE-PEAR share A, P, E; APPLE-LEAP share A, P, L, E; PEAR-LEAP share P, E, A
+    assert_eq!(crossword::max_possible_intersections(&["APPLE", "PEAR", "LEAP"]), 10);
+    assert_eq!(crossword::max_possible_intersections(&["apple::Fruit", "POPPY"]), 2);
+    assert_eq!(crossword::max_possible_intersections(&["ALPHA"]), 0);
+
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let words = ["BEARER", "ABOVE", "HERE", "NEVER", "INVALUABLE", "BOO", "BANANA", "AROUND", "ROE", "ROYAL"];
+    assert!(grid.count_intersections() <= crossword::max_possible_intersections(&words));
+}
+
+#[test]
+fn lint_clue_file() {
+    use crossword::ClueLintKind::*;
+    let lints = crossword::lint_clue_file("tests/resources/lint_problems.txt").unwrap();
+    let found: Vec<(usize, crossword::ClueLintKind)> = lints.into_iter().map(|lint| (lint.line_number, lint.kind)).collect();
+    assert_eq!(found, vec![(4, EnumerationMismatch("(4,5)".to_string(), "(5,4)".to_string())),
+                           (7, InvalidAnswerChar('1')),
+                           (8, EmptyAnswer),
+                           (9, DuplicateAnswer("SALESMAN".to_string(), 2)),
+                           (10, ShortAnswer("OX".to_string(), 3))]);
+
+    // EARTRUMPET is written as one word, so doesn't match the (3,7) in its clue
+    let lints = crossword::lint_clue_file("tests/resources/input_with_clues.txt").unwrap();
+    assert_eq!(lints, vec![crossword::ClueLint { line_number: 2, kind: EnumerationMismatch("(3,7)".to_string(), "(10)".to_string()) }]);
+    assert_eq!(crossword::lint_clue_contents("OX::Beast (2)::", 2), vec![]);
+    assert!(crossword::lint_clue_file("tests/resources/no_such_file.txt").is_err());
+}
+
+#[test]
+fn words_matching_pattern() {
+    let words = ["APE", "ace::Top card", "ARE", "APES", "EWE", "a-e"];
+    let pattern = [Some('A'), None, Some('E')];
+    assert_eq!(crossword::words_matching_pattern(&words, &pattern), vec!["APE", "ACE", "ARE"]);
+    assert_eq!(crossword::words_matching_pattern(&words, &[Some('a'), Some('p'), None]), vec!["APE"]);
+    assert_eq!(crossword::words_matching_pattern(&words, &[None, None, None, None]), vec!["APES"]);
+    assert_eq!(crossword::words_matching_pattern(&words, &[Some('Z'), None, None]), Vec::<String>::new());
+}
+
+#[test]
+fn partition_by_shared_letters() {
+    // APPLE, PEAR and REST are linked by shared letters, as are QUIZ and ZIG, but the two
+    // groups share no letters at all
+    let words = ["apple::Fruit", "QUIZ", "PEAR", "ZIG", "REST"];
+    assert_eq!(crossword::partition_by_shared_letters(&words),
+               vec![vec!["APPLE", "PEAR", "REST"], vec!["QUIZ", "ZIG"]]);
+
+    assert_eq!(crossword::partition_by_shared_letters(&["OX", "BEE"]), vec![vec!["OX"], vec!["BEE"]]);
+    assert_eq!(crossword::partition_by_shared_letters(&[]), Vec::<Vec<String>>::new());
+}
+
+#[test]
+fn answer_overlap() {
+    let theme = vec!["apple::Fruit", "BELLY FLOP::Bad dive", "cherry", "DATE::Day"];
+    let fill = vec!["Cherry::Red fruit", "belly-flop", "kiwi", "date::Dried fruit::DOWN", "12345"];
+    assert_eq!(crossword::answer_overlap(&theme, &fill), vec!["BELLYFLOP", "CHERRY", "DATE"]);
+    assert_eq!(crossword::answer_overlap(&theme, &[]), Vec::<String>::new());
+}
+
+#[test]
+fn average_intersections_no_placed_words() {
+    let mut grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.average_intersections_per_word(), 0.0);
+
+    grid.add_unplaced_word("BETA", "", None);
+    grid.unplace_word(0);
+    assert_eq!(grid.count_placed_words(), 0);
+    assert_eq!(grid.average_intersections_per_word(), 0.0);
+}
+
+#[test]
+fn longest_word() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let (_word_id, length) = grid.longest_word().unwrap();
+    assert_eq!(length, 10);
+    assert_eq!(grid.grid_span(), 10);
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.longest_word(), Some((0, 5)));
+    assert_eq!(grid.grid_span(), 5);
+}
+
+#[test]
+fn cell_checking() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let checking_map = grid.cell_checking_map();
+    assert_eq!(checking_map.len(), grid.count_filled_cells());
+    let checked = checking_map.values().filter(|s| **s == crossword::grid::CheckStatus::Checked).count();
+    assert_eq!(checked, grid.count_intersections());
+
+    // BEARER crosses BOO at its first letter
+    assert_eq!(checking_map.get(&crossword::grid::Location(0, 2)), Some(&crossword::grid::CheckStatus::Checked));
+    assert_eq!(checking_map.get(&crossword::grid::Location(0, 3)), Some(&crossword::grid::CheckStatus::Unchecked));
+    assert!(assert_approx_equal(grid.checked_cell_fraction(), 11.0 / 42.0));
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.checked_cell_fraction(), 0.0);
+}
+
+#[test]
+fn difficulty_estimate() {
+    // Every cell is checked and the words are short
+    let easy = crossword::grid::CrosswordGridBuilder::new().from_string("CAT\nA A\nNAP\n");
+    assert_eq!(easy.checked_cell_fraction(), 4.0 / 8.0);
+    assert!(assert_approx_equal(easy.average_word_length(), 3.0));
+    assert_eq!(easy.rare_letter_count(), 0);
+
+    // Long words with rare letters, crossing only once
+    let hard = crossword::grid::CrosswordGridBuilder::new().from_string("JUXTAPOSED \n    Z      \n    I      \n    MAZURKA\n    U      \n    T      \n    H      \n");
+    assert_eq!(hard.count_placed_words(), 3);
+    assert_eq!(hard.rare_letter_count(), 5);
+
+    assert!(easy.difficulty_estimate() < hard.difficulty_estimate());
+    assert!(easy.difficulty_estimate() >= 0.0);
+    assert!(hard.difficulty_estimate() <= 1.0);
+
+    let mut grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    grid.unplace_word(0);
+    assert_eq!(grid.difficulty_estimate(), 0.0);
+}
+
+#[test]
+fn playable_cell_count() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    // 11 of the 48 empty cells are black as they're directly before or after a word
+    assert_eq!(grid.playable_cell_count(), 79);
+    assert_eq!(grid.count_filled_cells() + grid.count_empty_cells(), 90);
+
+    // The buffer around the grid isn't playable
+    let mut grid = grid;
+    grid.fit_to_size_with_buffer(2);
+    assert_eq!(grid.playable_cell_count(), 79);
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.playable_cell_count(), 5);
+}
+
+#[test]
+fn word_fill_efficiency() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    assert!(assert_approx_equal(grid.word_fill_efficiency(), 42.0 / 79.0));
+    assert!(grid.word_fill_efficiency() > grid.count_filled_cells() as f64 / 90.0);
+
+    // The buffer around the grid doesn't change the efficiency
+    let mut grid = grid;
+    grid.fit_to_size_with_buffer(2);
+    assert!(assert_approx_equal(grid.word_fill_efficiency(), 42.0 / 79.0));
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.word_fill_efficiency(), 1.0);
+}
+
+#[test]
+fn words_per_row_and_col() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    // Row 2 has ABOVE and HERE across, and is crossed by BOO, BANANA, ROE and ROYAL
+    assert_eq!(grid.words_per_row(), vec![3, 2, 6, 3, 4, 3, 4, 2, 1]);
+    // Column 5 is crossed by BEARER, NEVER and INVALUABLE, and has AROUND down
+    let words_per_col = grid.words_per_col();
+    assert_eq!(words_per_col.len(), 10);
+    assert_eq!(words_per_col[0], 2);
+    assert_eq!(words_per_col[1], 4);
+    assert_eq!(words_per_col[5], 4);
+    assert_eq!(words_per_col[9], 2);
+
+    // The buffer around the grid doesn't add any rows or columns
+    let mut grid = grid;
+    grid.fit_to_size_with_buffer(2);
+    assert_eq!(grid.words_per_row(), vec![3, 2, 6, 3, 4, 3, 4, 2, 1]);
+    assert_eq!(grid.words_per_col(), words_per_col);
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.words_per_row(), vec![1]);
+    assert_eq!(grid.words_per_col(), vec![1; 5]);
+}
+
+#[test]
+fn letter_counts() {
+    // The central L is shared by the across and down ALE, so is only counted once
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+    let counts: Vec<(char, usize)> = grid.letter_counts().into_iter().collect();
+    assert_eq!(counts, vec![('A', 2), ('E', 2), ('L', 1)]);
+
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let counts = grid.letter_counts();
+    assert_eq!(counts.values().sum::<usize>(), grid.count_filled_cells());
+    assert_eq!(counts.get(&'B'), Some(&3));
+    assert_eq!(counts.get(&'Z'), None);
+}
+
+#[test]
+fn intersections() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let intersections = grid.intersections();
+    assert_eq!(intersections.len(), 11);
+    assert_eq!(intersections.len(), grid.count_intersections());
+
+    // BEARER crosses BOO and then ROE along the top row
+    assert_eq!(intersections[0].0, crossword::grid::Location(0, 2));
+    assert_eq!(intersections[1].0, crossword::grid::Location(0, 7));
+    assert_eq!(intersections[0].1, intersections[1].1);
+    assert_ne!(intersections[0].2, intersections[1].2);
+    for (_location, across_word_id, down_word_id) in intersections {
+        assert_ne!(across_word_id, down_word_id);
+    }
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert!(grid.intersections().is_empty());
+}
+
+#[test]
+fn weighted_crossing_edges() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let edges = grid.weighted_crossing_edges();
+    assert_eq!(edges.len(), grid.count_intersections());
+    for ((first_word_id, second_word_id), weight) in edges {
+        assert!(first_word_id < second_word_id);
+        assert_eq!(weight, 1);
+    }
+
+    // Giving the bottom word the same id as the top word makes it cross each down word twice
+    let mut grid = crossword::grid::CrosswordGridBuilder::new().from_string("CAT\nA A\nNAP\n");
+    let (cat, _) = grid.word_at(crossword::grid::Location(0, 0), crossword::grid::Direction::Across).unwrap();
+    let (nap, _) = grid.word_at(crossword::grid::Location(2, 0), crossword::grid::Direction::Across).unwrap();
+    let (can, _) = grid.word_at(crossword::grid::Location(0, 0), crossword::grid::Direction::Down).unwrap();
+    let (tap, _) = grid.word_at(crossword::grid::Location(0, 2), crossword::grid::Direction::Down).unwrap();
+    assert_eq!(grid.weighted_crossing_edges().len(), 4);
+    grid.update_word_id(nap, cat);
+    let mut expected = vec![((std::cmp::min(cat, can), std::cmp::max(cat, can)), 2),
+                            ((std::cmp::min(cat, tap), std::cmp::max(cat, tap)), 2)];
+    expected.sort();
+    assert_eq!(grid.weighted_crossing_edges(), expected);
+}
+
+#[test]
+fn pendant_words() {
+    // SOIL crosses both SAD and INVALUABLE, which each hang off a single crossing
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string("SAD       \nO         \nINVALUABLE\nL         \n");
+    assert_eq!(grid.count_placed_words(), 3);
+    let pendants = grid.pendant_words();
+    let lengths: Vec<usize> = pendants.iter().map(|(_word_id, length)| *length).collect();
+    assert_eq!(pendants.len(), 2);
+    assert!(lengths.contains(&3));
+    assert!(lengths.contains(&10));
+
+    // Every word in the simple example crosses at least two others
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    assert!(grid.pendant_words().is_empty());
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert!(grid.pendant_words().is_empty());
+}
+
+#[test]
+fn components_if_removed() {
+    // SOIL is the only link between SAD and INVALUABLE
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string("SAD       \nO         \nINVALUABLE\nL         \n");
+    let intersections = grid.intersections();
+    let (_location, sad_word_id, soil_word_id) = intersections[0];
+    let (_location, invaluable_word_id, _soil_word_id) = intersections[1];
+    assert_eq!(grid.components_if_removed(soil_word_id), vec![1, 1]);
+    assert_eq!(grid.components_if_removed(sad_word_id), vec![2]);
+    assert_eq!(grid.components_if_removed(invaluable_word_id), vec![2]);
+    assert_eq!(grid.count_placed_words(), 3);
+
+    // Every word in the simple example can be removed without splitting the grid
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    for (_location, across_word_id, down_word_id) in grid.intersections() {
+        assert_eq!(grid.components_if_removed(across_word_id), vec![9]);
+        assert_eq!(grid.components_if_removed(down_word_id), vec![9]);
+    }
+    assert!(grid.components_if_removed(100).is_empty());
+}
+
+#[test]
+fn word_at() {
+    use crossword::grid::Direction::{Across,Down};
+    use crossword::grid::Location;
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+
+    // The middle of INVALUABLE, which isn't crossed by a down word
+    let (word_id, text) = grid.word_at(Location(6, 4), Across).unwrap();
+    assert_eq!(text, "INVALUABLE");
+    assert_eq!(grid.word_at(Location(6, 9), Across), Some((word_id, text)));
+    assert_eq!(grid.word_at(Location(6, 4), Down), None);
+
+    // BEARER crosses BOO at its first letter
+    assert_eq!(grid.word_at(Location(0, 2), Across).map(|(_id, text)| text), Some("BEARER".to_string()));
+    assert_eq!(grid.word_at(Location(0, 2), Down).map(|(_id, text)| text), Some("BOO".to_string()));
+
+    // Empty, black and out of range cells
+    assert_eq!(grid.word_at(Location(0, 0), Across), None);
+    assert_eq!(grid.word_at(Location(0, 1), Across), None);
+    assert_eq!(grid.word_at(Location(100, 100), Down), None);
+}
+
+#[test]
+fn word_shape_counts() {
+    use crossword::grid::Direction::{Across,Down};
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let counts: Vec<((usize, crossword::grid::Direction), usize)> = grid.word_shape_counts().into_iter().collect();
+    assert_eq!(counts, vec![((3, Down), 2),
+                            ((4, Across), 1),
+                            ((5, Across), 2), ((5, Down), 1),
+                            ((6, Across), 1), ((6, Down), 2),
+                            ((10, Across), 1)]);
+    assert_eq!(grid.word_shape_counts().values().sum::<usize>(), grid.count_placed_words());
+}
+
+#[test]
+fn spine_ratio() {
+    // Each word only crosses the next, so every word is in the longest chain
+    let stringy = crossword::grid::CrosswordGridBuilder::new().from_string("CAT\nA  \nRAT\n  O\n  E\n");
+    assert_eq!(stringy.count_placed_words(), 4);
+    assert_eq!(stringy.longest_word_chain(), 4);
+    assert_eq!(stringy.spine_ratio(), 1.0);
+
+    let woven = crossword::grid::CrosswordGridBuilder::new().from_string("CAT\nA A\nNAP\n");
+    assert_eq!(woven.longest_word_chain(), 3);
+    assert_eq!(woven.spine_ratio(), 0.75);
+    assert!(woven.spine_ratio() < stringy.spine_ratio());
+
+    let mut grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    grid.unplace_word(0);
+    assert_eq!(grid.spine_ratio(), 0.0);
+}
+
+#[test]
+fn intersection_centrality() {
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.intersection_centrality(), 0.0);
+
+    // Crossing in the middle of both words
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+    assert_eq!(grid.intersection_centrality(), 1.0);
+
+    // Crossing at the start of both words
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string("ALE\nL  \nE  \n");
+    assert_eq!(grid.intersection_centrality(), 0.0);
+
+    // Crossing at the end of one word and the middle of the other
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string("  A\nALE\n  E\n");
+    assert!(assert_approx_equal(grid.intersection_centrality(), 0.5));
+}
+
+#[test]
+fn intersection_letter_histogram() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+    let counts: Vec<(char, usize)> = grid.intersection_letter_histogram().into_iter().collect();
+    assert_eq!(counts, vec![('L', 1)]);
+
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let counts: Vec<(char, usize)> = grid.intersection_letter_histogram().into_iter().collect();
+    assert_eq!(counts, vec![('B', 2), ('E', 1), ('L', 1), ('N', 2), ('O', 1), ('R', 3), ('U', 1)]);
+    assert_eq!(counts.iter().map(|(_c, count)| count).sum::<usize>(), grid.count_intersections());
+}
+
+#[test]
+fn custom_blank_char() {
+    let contents = std::fs::read_to_string("tests/resources/simple_example.txt").unwrap();
+    let hash_delimited = contents.replace(' ', "#");
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(&contents);
+    let hash_grid = crossword::grid::CrosswordGridBuilder::new().with_blank_char('#').from_string(&hash_delimited);
+    assert_eq!(hash_grid.to_string(), grid.to_string());
+    assert_eq!(hash_grid.count_placed_words(), grid.count_placed_words());
+    assert_eq!(hash_grid.count_intersections(), grid.count_intersections());
+
+    let dot_grid = crossword::grid::CrosswordGridBuilder::new().with_blank_char('.').from_string(".A.\nALE\n.E.\n");
+    assert_eq!(dot_grid.to_string(), " A \nALE\n E \n");
+}
+
+#[test]
+fn fully_checked() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string("AB\nCD\n");
+    assert_eq!(grid.count_placed_words(), 4);
+    assert!(grid.fully_checked());
+
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+    assert!(!grid.fully_checked());
+
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    assert!(!grid.fully_checked());
+}
+
+#[test]
+fn cell_at() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+    let cell = grid.cell_at(crossword::grid::Location(1, 1)).unwrap();
+    assert_eq!(cell.letter, Some('L'));
+    assert!(!cell.is_black);
+    assert!(cell.across_word_id.is_some());
+    assert!(cell.down_word_id.is_some());
+    assert_ne!(cell.across_word_id, cell.down_word_id);
+
+    let cell = grid.cell_at(crossword::grid::Location(1, 0)).unwrap();
+    assert_eq!(cell.letter, Some('A'));
+    assert_eq!(cell.across_word_id, grid.cell_at(crossword::grid::Location(1, 1)).unwrap().across_word_id);
+    assert_eq!(cell.down_word_id, None);
+
+    let cell = grid.cell_at(crossword::grid::Location(0, 0)).unwrap();
+    assert_eq!(cell.letter, None);
+
+    assert_eq!(grid.cell_at(crossword::grid::Location(100, 100)), None);
+}
+
+#[test]
+fn word_frontier() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let never_id = grid.cell_at(crossword::grid::Location(4, 1)).unwrap().across_word_id.unwrap();
+    let frontier = grid.word_frontier(never_id);
+
+    // The cells above and below both ends of NEVER are filled, so aren't in the frontier
+    let expected: Vec<crossword::grid::Location> = [(3, 2), (3, 3), (3, 4), (5, 2), (5, 3), (5, 4)].iter()
+        .map(|(row, col)| crossword::grid::Location(*row, *col))
+        .collect();
+    assert_eq!(frontier, expected);
+    for location in frontier {
+        assert_eq!(grid.cell_at(location).unwrap().letter, None);
+    }
+
+    assert_eq!(grid.word_frontier(1000), vec![]);
+}
+
+#[test]
+fn words_on_edge() {
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.words_on_edge().len(), 1);
+
+    // Only BANANA, ROYAL and NEVER don't reach the border
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let edge_words = grid.words_on_edge();
+    assert_eq!(edge_words.len(), 7);
+    let banana_id = grid.cell_at(crossword::grid::Location(2, 1)).unwrap().down_word_id.unwrap();
+    let royal_id = grid.cell_at(crossword::grid::Location(2, 8)).unwrap().down_word_id.unwrap();
+    let never_id = grid.cell_at(crossword::grid::Location(4, 1)).unwrap().across_word_id.unwrap();
+    let bearer_id = grid.cell_at(crossword::grid::Location(0, 2)).unwrap().across_word_id.unwrap();
+    let around_id = grid.cell_at(crossword::grid::Location(3, 5)).unwrap().down_word_id.unwrap();
+    for word_id in [banana_id, royal_id, never_id].iter() {
+        assert!(!edge_words.contains(word_id));
+    }
+    assert!(edge_words.contains(&bearer_id));
+    assert!(edge_words.contains(&around_id));
+
+    // The buffer around the grid doesn't change which words are on the edge
+    let mut grid = grid;
+    grid.fit_to_size_with_buffer(2);
+    assert_eq!(grid.words_on_edge(), edge_words);
+}
+
+#[test]
+fn fill_mask() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let mask = grid.fill_mask();
+    assert_eq!(mask.dim(), grid.get_grid_dimensions());
+    assert_eq!(mask.sum() as usize, grid.count_filled_cells());
+    assert_eq!(mask[[0, 2]], 1);
+    assert_eq!(mask[[0, 0]], 0);
+
+    // The buffer around the grid doesn't change the mask
+    let mut grid = grid;
+    grid.fit_to_size_with_buffer(2);
+    assert_eq!(grid.fill_mask(), mask);
+}
+
+#[test]
+fn center_of_mass() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+    assert_eq!(grid.center_of_mass(), (0.5, 0.5));
+
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.center_of_mass(), (0.5, 0.5));
+
+    // Two of the three letters are in the top row and the left column
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string("AB\nC \n");
+    let (row, col) = grid.center_of_mass();
+    assert!(assert_approx_equal(row, 5.0 / 12.0));
+    assert!(assert_approx_equal(col, 5.0 / 12.0));
+
+    // The buffer around the grid doesn't change the centre of mass
+    let mut grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let (row, col) = grid.center_of_mass();
+    grid.fit_to_size_with_buffer(2);
+    let (padded_row, padded_col) = grid.center_of_mass();
+    assert!(assert_approx_equal(padded_row, row));
+    assert!(assert_approx_equal(padded_col, col));
+}
+
+#[test]
+fn compactness() {
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.compactness(), 1.0);
+
+    // The central L is surrounded by letters, so isn't on the perimeter
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_string(" A \nALE\n E \n");
+    assert_eq!(grid.count_filled_cells(), 5);
+    assert_eq!(grid.compactness(), 5.0 / 4.0);
+
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    assert!(grid.compactness() >= 1.0);
+}
+
 #[test]
 #[should_panic]
 fn check_disconnected() {
-    crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/disconnected.txt");
+    crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/disconnected.txt").unwrap();
 }
 
 #[test]
@@ -39,7 +571,7 @@ fn single_word() {
 
 #[test]
 fn add_random_words() {
-    let mut grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt");
+    let mut grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/everyman_starter.txt").unwrap();
     grid.add_unplaced_word("PROBONO", "", None);
     grid.add_unplaced_word("PASTURE", "", None);
     grid.add_unplaced_word("VETO", "", None);
@@ -58,7 +590,7 @@ fn test_generator() {
     crossword::logging::init_logger(true);
     let words = vec!["BEARER", "ABOVE", "HERE", "INVALUABLE", "BANANA", "ROYAL", "AROUND", "ROE"];
     let mut generator = crossword::generator::CrosswordGenerator::new_from_singletons(words, HashMap::new());
-    generator.generate();
+    generator.generate().unwrap();
 }
 
 #[test]
@@ -68,15 +600,15 @@ fn test_generator_fifteen_squared() {
     settings_map.insert("num-children", 2);
     settings_map.insert("num-per-gen", 5);
     settings_map.insert("max-rounds", 5);
-    let mut generator = crossword::generator::CrosswordGenerator::new_from_file("tests/resources/fifteensquared/quiptic-1109-by-pan.txt", settings_map.clone());
+    let mut generator = crossword::generator::CrosswordGenerator::new_from_file("tests/resources/fifteensquared/quiptic-1109-by-pan.txt", settings_map.clone()).unwrap();
 
-    let results = generator.generate();
+    let results = generator.generate().unwrap();
     for grid in results.iter() {
         debug!("{}", grid.to_string());
     }
 
-    let mut generator2 = crossword::generator::CrosswordGenerator::new_from_file("tests/resources/fifteensquared/quiptic-1109-by-pan.txt", settings_map);
-    let results2 = generator2.generate();
+    let mut generator2 = crossword::generator::CrosswordGenerator::new_from_file("tests/resources/fifteensquared/quiptic-1109-by-pan.txt", settings_map).unwrap();
+    let results2 = generator2.generate().unwrap();
 
     for grid in results2.iter() {
         debug!("{}", grid.to_string());
@@ -99,8 +631,8 @@ fn test_generator_fifteen_squared_branching() {
     settings_map.insert("num-children", 3);
     settings_map.insert("num-per-gen", 100);
     settings_map.insert("max-rounds", 1);
-    let mut generator = crossword::generator::CrosswordGenerator::new_from_file("tests/resources/fifteensquared/quiptic-1109-by-pan.txt", settings_map);
-    let results = generator.generate();
+    let mut generator = crossword::generator::CrosswordGenerator::new_from_file("tests/resources/fifteensquared/quiptic-1109-by-pan.txt", settings_map).unwrap();
+    let results = generator.generate().unwrap();
     for grid in results.iter() {
         debug!("{}", grid.to_string());
     }
@@ -108,18 +640,92 @@ fn test_generator_fifteen_squared_branching() {
 
 #[test]
 fn test_printing() {
-    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
-    let mut printer = crossword::grid::CrosswordPrinter::new(grid);
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let mut printer = crossword::grid::CrosswordPrinter::new_default(grid);
     println!("{}", printer.print());
     debug!("{:#?}", printer);
 }
 
+fn clue_numbers(latex: &str) -> Vec<String> {
+    latex.lines()
+        .filter(|line| line.starts_with("\\CrosswordClue{"))
+        .map(|line| line["\\CrosswordClue{".len()..].split('}').next().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_print_both() {
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    let mut printer = crossword::grid::CrosswordPrinter::new_default(grid);
+    let (blank, solution) = printer.print_both();
+
+    assert_eq!(clue_numbers(&blank).len(), 10);
+    assert_eq!(clue_numbers(&blank), clue_numbers(&solution));
+    assert!(!blank.contains("BEARER"));
+    assert!(solution.contains("{BEARER}"));
+    assert!(solution.contains("[Sf]"));
+
+    // Printing again gives the same result, and the printer's own settings are kept
+    assert_eq!(printer.print_both(), (blank.clone(), solution));
+    assert_eq!(printer.print(), blank);
+}
+
+#[test]
+fn test_print_many() {
+    let grids = vec![
+        crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap(),
+        crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt").unwrap(),
+        crossword::grid::CrosswordGrid::new_single_word("ALPHA"),
+    ];
+    let latex = crossword::grid::CrosswordPrinter::print_many(grids.clone(), true);
+    assert_eq!(latex.matches("\\documentclass").count(), 1);
+    assert_eq!(latex.matches("\\begin{Puzzle}").count(), 3);
+    assert_eq!(latex.matches("\\section*{Across}").count(), 3);
+    assert!(latex.contains("{BEARER}"));
+
+    // Only try compiling to PDF if pdflatex is installed
+    if std::process::Command::new("pdflatex").arg("--version").output().is_ok() {
+        let output_root = std::env::temp_dir().join("crossword_print_many");
+        let output_root = output_root.to_str().unwrap();
+        crossword::grid::CrosswordPrinter::print_many_to_pdf(grids, false, output_root);
+        let tex = std::fs::read_to_string(format!("{}.tex", output_root)).unwrap();
+        assert_eq!(tex.matches("\\begin{Puzzle}").count(), 3);
+        assert!(std::path::Path::new(&format!("{}.pdf", output_root)).exists());
+    }
+}
+
 #[test]
 fn test_average_intersections() {
     crossword::logging::init_logger(true);
-    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt");
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/bear_button.txt").unwrap();
     assert_approx_equal(grid.average_intersections_per_word(), (1.0/4.0 + 2.0/3.0 + 2.0/6.0 + 1.0/4.0)/4.0);
 
-    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt");
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
     assert_approx_equal(grid.average_intersections_per_word(), (2.0/6.0 + 2.0/5.0 + 2.0/4.0 + 2.0/5.0 + 3.0/10.0 + 2.0/3.0 + 2.0/3.0 + 3.0/6.0 + 2.0/6.0 + 2.0/5.0)/10.0);
 }
+
+#[test]
+fn seed_reach() -> Result<(), crossword::grid::CrosswordError> {
+    let mut grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.seed_reach(), 0);
+
+    // ALPHA -> ARRIVAL -> LEVEL forms a path, with the seed word at one end
+    let arrival_word_id = grid.add_unplaced_word("ARRIVAL", "", None);
+    grid.place_word_in_cell(crossword::grid::Location(0, 0), arrival_word_id, 0, crossword::grid::Direction::Down)?;
+    assert_eq!(grid.seed_reach(), 1);
+    let level_word_id = grid.add_unplaced_word("LEVEL", "", None);
+    grid.place_word_in_cell(crossword::grid::Location(6, 0), level_word_id, 0, crossword::grid::Direction::Across)?;
+    assert_eq!(grid.seed_reach(), 2);
+    Ok(())
+}
+
+#[test]
+fn longest_word_chain() {
+    let grid = crossword::grid::CrosswordGrid::new_single_word("ALPHA");
+    assert_eq!(grid.longest_word_chain(), 1);
+
+    // The shortest route from NEVER to ROE is NEVER -> BANANA -> ABOVE -> BOO -> BEARER -> ROE
+    let grid = crossword::grid::CrosswordGridBuilder::new().from_file("tests/resources/simple_example.txt").unwrap();
+    assert_eq!(grid.to_graph().longest_path_len(), 5);
+    assert_eq!(grid.longest_word_chain(), 6);
+}